@@ -0,0 +1,417 @@
+//! Helpers for programs invoking the pools, all the accounts of an instruction
+//! are taken from the pool address and its loaded `PoolState`.
+//!
+//! The instructions are invoked with `invoke` or `invoke_signed`, passing the
+//! account infos of the instruction in any order.
+
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+
+/// Addresses of a pool and its PDAs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub pool_id: Pubkey,
+    pub amm_config: Pubkey,
+    /// Pool vault and lp mint authority
+    pub authority: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub lp_mint: Pubkey,
+    pub observation_state: Pubkey,
+    pub token_0_mint_registry_entry: Pubkey,
+    pub token_1_mint_registry_entry: Pubkey,
+}
+
+/// Vault, mint and token program of one side of a swap
+struct SwapSide {
+    vault: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    mint_registry_entry: Pubkey,
+}
+
+impl PoolKeys {
+    /// The vaults and observation are the keys stored on the pool state and the authority is
+    /// derived from its stored bump, only the registry entries, which have no bump stored,
+    /// are searched
+    pub fn new(pool_id: Pubkey, pool_state: &PoolState) -> Result<Self> {
+        let program_id = crate::id();
+        let authority = Pubkey::create_program_address(
+            &[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]],
+            &program_id,
+        )
+        .map_err(|_| ErrorCode::InvalidInput)?;
+        let (token_0_mint_registry_entry, _) = Pubkey::find_program_address(
+            &[
+                MINT_REGISTRY_SEED.as_bytes(),
+                pool_state.token_0_mint.as_ref(),
+            ],
+            &program_id,
+        );
+        let (token_1_mint_registry_entry, _) = Pubkey::find_program_address(
+            &[
+                MINT_REGISTRY_SEED.as_bytes(),
+                pool_state.token_1_mint.as_ref(),
+            ],
+            &program_id,
+        );
+        Ok(Self {
+            pool_id,
+            amm_config: pool_state.amm_config,
+            authority,
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            token_0_mint: pool_state.token_0_mint,
+            token_1_mint: pool_state.token_1_mint,
+            token_0_program: pool_state.token_0_program,
+            token_1_program: pool_state.token_1_program,
+            lp_mint: pool_state.lp_mint,
+            observation_state: pool_state.observation_key,
+            token_0_mint_registry_entry,
+            token_1_mint_registry_entry,
+        })
+    }
+
+    /// Input and output side of a swap paying `input_mint`
+    fn swap_sides(&self, input_mint: Pubkey) -> Result<(SwapSide, SwapSide)> {
+        let side_0 = SwapSide {
+            vault: self.token_0_vault,
+            mint: self.token_0_mint,
+            token_program: self.token_0_program,
+            mint_registry_entry: self.token_0_mint_registry_entry,
+        };
+        let side_1 = SwapSide {
+            vault: self.token_1_vault,
+            mint: self.token_1_mint,
+            token_program: self.token_1_program,
+            mint_registry_entry: self.token_1_mint_registry_entry,
+        };
+        if input_mint == self.token_0_mint {
+            Ok((side_0, side_1))
+        } else if input_mint == self.token_1_mint {
+            Ok((side_1, side_0))
+        } else {
+            err!(ErrorCode::InvalidInput)
+        }
+    }
+
+    fn swap_accounts(
+        &self,
+        payer: Pubkey,
+        input_token_account: Pubkey,
+        output_token_account: Pubkey,
+        input_mint: Pubkey,
+    ) -> Result<crate::accounts::Swap> {
+        let (input, output) = self.swap_sides(input_mint)?;
+        Ok(crate::accounts::Swap {
+            payer,
+            authority: self.authority,
+            amm_config: self.amm_config,
+            pool_state: self.pool_id,
+            input_token_account: Some(input_token_account),
+            output_token_account: Some(output_token_account),
+            input_vault: input.vault,
+            output_vault: output.vault,
+            input_token_program: input.token_program,
+            output_token_program: output.token_program,
+            input_token_mint: input.mint,
+            output_token_mint: output.mint,
+            observation_state: self.observation_state,
+            input_mint_registry_entry: Some(input.mint_registry_entry),
+            output_mint_registry_entry: Some(output.mint_registry_entry),
+            early_access: None,
+            launch_buy_state: None,
+            system_program: None,
+            referrer_token_account: None,
+            temp_wsol_account: None,
+        })
+    }
+
+    /// `swap_base_input` paying `amount_in` of `input_mint`
+    pub fn swap_base_input(
+        &self,
+        payer: Pubkey,
+        input_token_account: Pubkey,
+        output_token_account: Pubkey,
+        input_mint: Pubkey,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<Instruction> {
+        let accounts =
+            self.swap_accounts(payer, input_token_account, output_token_account, input_mint)?;
+        Ok(Instruction {
+            program_id: crate::id(),
+            accounts: accounts.to_account_metas(None),
+            data: crate::instruction::SwapBaseInput {
+                amount_in,
+                minimum_amount_out,
+            }
+            .data(),
+        })
+    }
+
+    /// `swap_base_output` receiving `amount_out` of the other mint than `input_mint`
+    pub fn swap_base_output(
+        &self,
+        payer: Pubkey,
+        input_token_account: Pubkey,
+        output_token_account: Pubkey,
+        input_mint: Pubkey,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Result<Instruction> {
+        let accounts =
+            self.swap_accounts(payer, input_token_account, output_token_account, input_mint)?;
+        Ok(Instruction {
+            program_id: crate::id(),
+            accounts: accounts.to_account_metas(None),
+            data: crate::instruction::SwapBaseOutput {
+                max_amount_in,
+                amount_out,
+            }
+            .data(),
+        })
+    }
+
+    /// `deposit` minting `lp_token_amount`
+    #[allow(clippy::too_many_arguments)]
+    pub fn deposit(
+        &self,
+        owner: Pubkey,
+        owner_lp_token: Pubkey,
+        token_0_account: Pubkey,
+        token_1_account: Pubkey,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Instruction {
+        let accounts = crate::accounts::Deposit {
+            owner,
+            authority: self.authority,
+            pool_state: self.pool_id,
+            owner_lp_token,
+            token_0_account: Some(token_0_account),
+            token_1_account: Some(token_1_account),
+            token_0_vault: self.token_0_vault,
+            token_1_vault: self.token_1_vault,
+            token_program: anchor_spl::token::ID,
+            token_program_2022: anchor_spl::token_2022::ID,
+            vault_0_mint: self.token_0_mint,
+            vault_1_mint: self.token_1_mint,
+            lp_mint: self.lp_mint,
+            amm_config: self.amm_config,
+            system_program: None,
+            temp_wsol_account: None,
+        };
+        Instruction {
+            program_id: crate::id(),
+            accounts: accounts.to_account_metas(None),
+            data: crate::instruction::Deposit {
+                lp_token_amount,
+                maximum_token_0_amount,
+                maximum_token_1_amount,
+            }
+            .data(),
+        }
+    }
+
+    /// `withdraw` burning `lp_token_amount`
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdraw(
+        &self,
+        owner: Pubkey,
+        owner_lp_token: Pubkey,
+        token_0_account: Pubkey,
+        token_1_account: Pubkey,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Instruction {
+        let accounts = crate::accounts::Withdraw {
+            owner,
+            authority: self.authority,
+            pool_state: self.pool_id,
+            owner_lp_token,
+            token_0_account: Some(token_0_account),
+            token_1_account: Some(token_1_account),
+            token_0_vault: self.token_0_vault,
+            token_1_vault: self.token_1_vault,
+            token_program: anchor_spl::token::ID,
+            token_program_2022: anchor_spl::token_2022::ID,
+            vault_0_mint: self.token_0_mint,
+            vault_1_mint: self.token_1_mint,
+            lp_mint: self.lp_mint,
+            memo_program: spl_memo::id(),
+            amm_config: self.amm_config,
+            system_program: None,
+            temp_wsol_account: None,
+        };
+        Instruction {
+            program_id: crate::id(),
+            accounts: accounts.to_account_metas(None),
+            data: crate::instruction::Withdraw {
+                lp_token_amount,
+                minimum_token_0_amount,
+                minimum_token_1_amount,
+            }
+            .data(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod cpi_helpers_test {
+    use super::*;
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    fn pool_keys() -> (PoolState, PoolKeys) {
+        let (_, auth_bump) =
+            Pubkey::find_program_address(&[crate::AUTH_SEED.as_bytes()], &crate::id());
+        let pool_state = PoolState {
+            amm_config: Pubkey::new_unique(),
+            token_0_vault: Pubkey::new_unique(),
+            token_1_vault: Pubkey::new_unique(),
+            lp_mint: Pubkey::new_unique(),
+            token_0_mint: Pubkey::new_unique(),
+            token_1_mint: Pubkey::new_unique(),
+            token_0_program: anchor_spl::token::ID,
+            token_1_program: anchor_spl::token_2022::ID,
+            observation_key: Pubkey::new_unique(),
+            auth_bump,
+            ..Default::default()
+        };
+        let keys = PoolKeys::new(Pubkey::new_unique(), &pool_state).unwrap();
+        (pool_state, keys)
+    }
+
+    #[test]
+    fn pool_keys_of_the_pool_state() {
+        let (pool_state, keys) = pool_keys();
+        assert_eq!(
+            keys.authority,
+            Pubkey::find_program_address(&[crate::AUTH_SEED.as_bytes()], &crate::id()).0
+        );
+        assert_eq!(keys.token_0_vault, pool_state.token_0_vault);
+        assert_eq!(keys.token_1_vault, pool_state.token_1_vault);
+        assert_eq!(keys.observation_state, pool_state.observation_key);
+
+        // a bump off the curve can't derive the authority
+        let mut pool_state = pool_state;
+        pool_state.auth_bump = (0..=u8::MAX)
+            .find(|bump| {
+                Pubkey::create_program_address(
+                    &[crate::AUTH_SEED.as_bytes(), &[*bump]],
+                    &crate::id(),
+                )
+                .is_err()
+            })
+            .unwrap();
+        assert!(PoolKeys::new(keys.pool_id, &pool_state).is_err());
+    }
+
+    #[test]
+    fn swap_metas_match_the_swap_layout() {
+        let (pool_state, keys) = pool_keys();
+        let payer = Pubkey::new_unique();
+        let input_token_account = Pubkey::new_unique();
+        let output_token_account = Pubkey::new_unique();
+        let instruction = keys
+            .swap_base_input(
+                payer,
+                input_token_account,
+                output_token_account,
+                pool_state.token_1_mint,
+                1,
+                1,
+            )
+            .unwrap();
+        let omitted = AccountMeta::new_readonly(crate::id(), false);
+        assert_eq!(
+            instruction.accounts,
+            vec![
+                AccountMeta::new_readonly(payer, true),
+                AccountMeta::new_readonly(keys.authority, false),
+                AccountMeta::new_readonly(pool_state.amm_config, false),
+                AccountMeta::new(keys.pool_id, false),
+                AccountMeta::new(input_token_account, false),
+                AccountMeta::new(output_token_account, false),
+                AccountMeta::new(pool_state.token_1_vault, false),
+                AccountMeta::new(pool_state.token_0_vault, false),
+                AccountMeta::new_readonly(pool_state.token_1_program, false),
+                AccountMeta::new_readonly(pool_state.token_0_program, false),
+                AccountMeta::new_readonly(pool_state.token_1_mint, false),
+                AccountMeta::new_readonly(pool_state.token_0_mint, false),
+                AccountMeta::new(pool_state.observation_key, false),
+                AccountMeta::new_readonly(keys.token_1_mint_registry_entry, false),
+                AccountMeta::new_readonly(keys.token_0_mint_registry_entry, false),
+                // early_access, launch_buy_state, system_program, referrer_token_account
+                // and temp_wsol_account
+                omitted.clone(),
+                omitted.clone(),
+                omitted.clone(),
+                omitted.clone(),
+                omitted,
+            ]
+        );
+
+        // a mint not in the pool
+        assert!(keys
+            .swap_base_input(
+                payer,
+                input_token_account,
+                output_token_account,
+                Pubkey::new_unique(),
+                1,
+                1,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn deposit_metas_match_the_deposit_layout() {
+        let (pool_state, keys) = pool_keys();
+        let owner = Pubkey::new_unique();
+        let owner_lp_token = Pubkey::new_unique();
+        let token_0_account = Pubkey::new_unique();
+        let token_1_account = Pubkey::new_unique();
+        let instruction = keys.deposit(
+            owner,
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+            1,
+            1,
+            1,
+        );
+        let omitted = AccountMeta::new_readonly(crate::id(), false);
+        assert_eq!(
+            instruction.accounts,
+            vec![
+                AccountMeta::new_readonly(owner, true),
+                AccountMeta::new_readonly(keys.authority, false),
+                AccountMeta::new(keys.pool_id, false),
+                AccountMeta::new(owner_lp_token, false),
+                AccountMeta::new(token_0_account, false),
+                AccountMeta::new(token_1_account, false),
+                AccountMeta::new(pool_state.token_0_vault, false),
+                AccountMeta::new(pool_state.token_1_vault, false),
+                AccountMeta::new_readonly(anchor_spl::token::ID, false),
+                AccountMeta::new_readonly(anchor_spl::token_2022::ID, false),
+                AccountMeta::new_readonly(pool_state.token_0_mint, false),
+                AccountMeta::new_readonly(pool_state.token_1_mint, false),
+                AccountMeta::new(pool_state.lp_mint, false),
+                AccountMeta::new_readonly(pool_state.amm_config, false),
+                // system_program and temp_wsol_account
+                omitted.clone(),
+                omitted,
+            ]
+        );
+    }
+}
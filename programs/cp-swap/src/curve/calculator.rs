@@ -145,6 +145,40 @@ impl CurveCalculator {
         })
     }
 
+    /// Get the amount of source token to swap when depositing single sided,
+    /// the fee-less optimal amount plus the swap fee charged on it.
+    pub fn single_sided_swap_amount(
+        source_amount: u128,
+        swap_source_amount: u128,
+        total_fees: u64,
+    ) -> Option<u128> {
+        let swap_amount =
+            ConstantProductCurve::single_sided_swap_amount(source_amount, swap_source_amount)?
+                .checked_add(total_fees as u128)?;
+        if swap_amount >= source_amount {
+            return None;
+        }
+        Some(swap_amount)
+    }
+
+    /// Get the amount of pool tokens for the given amounts of trading tokens,
+    /// provided the total trading tokens and supply of pool tokens.
+    pub fn trading_tokens_to_lp_tokens(
+        token_0_amount: u128,
+        token_1_amount: u128,
+        lp_token_supply: u128,
+        swap_token_0_amount: u128,
+        swap_token_1_amount: u128,
+    ) -> Option<u128> {
+        ConstantProductCurve::trading_tokens_to_lp_tokens(
+            token_0_amount,
+            token_1_amount,
+            lp_token_supply,
+            swap_token_0_amount,
+            swap_token_1_amount,
+        )
+    }
+
     /// Get the amount of trading tokens for the given amount of pool tokens,
     /// provided the total trading tokens and supply of pool tokens.
     pub fn lp_tokens_to_trading_tokens(
@@ -2,7 +2,7 @@
 
 use crate::{
     curve::calculator::{RoundDirection, TradingTokenResult},
-    utils::CheckedCeilDiv,
+    utils::{CheckedCeilDiv, U256},
 };
 
 /// ConstantProductCurve struct implementing CurveCalculator
@@ -90,6 +90,41 @@ impl ConstantProductCurve {
             token_1_amount,
         })
     }
+
+    /// Get the amount of pool tokens for the given amounts of trading tokens,
+    /// provided the total trading tokens and supply of pool tokens.
+    ///
+    /// The inverse of `lp_tokens_to_trading_tokens` rounded down, so the ceiling
+    /// trading tokens of the result never exceed the given amounts
+    pub fn trading_tokens_to_lp_tokens(
+        token_0_amount: u128,
+        token_1_amount: u128,
+        lp_token_supply: u128,
+        swap_token_0_amount: u128,
+        swap_token_1_amount: u128,
+    ) -> Option<u128> {
+        let lp_token_0_amount = token_0_amount
+            .checked_mul(lp_token_supply)?
+            .checked_div(swap_token_0_amount)?;
+        let lp_token_1_amount = token_1_amount
+            .checked_mul(lp_token_supply)?
+            .checked_div(swap_token_1_amount)?;
+        Some(std::cmp::min(lp_token_0_amount, lp_token_1_amount))
+    }
+
+    /// Get the amount of source token to swap so that the rest of the source
+    /// amount and the swapped destination amount are in the pool ratio after the swap.
+    ///
+    /// (a - x) / (x0 + x) = delta_y / (y0 - delta_y) = x / x0
+    /// x = sqrt(x0 * (x0 + a)) - x0
+    pub fn single_sided_swap_amount(source_amount: u128, swap_source_amount: u128) -> Option<u128> {
+        let product = U256::from(swap_source_amount)
+            .checked_mul(U256::from(swap_source_amount.checked_add(source_amount)?))?;
+        product
+            .integer_sqrt()
+            .as_u128()
+            .checked_sub(swap_source_amount)
+    }
 }
 
 #[cfg(test)]
@@ -133,6 +168,45 @@ mod tests {
         check_pool_token_rate(5, 501, 2, 10, 1, 101);
     }
 
+    #[test]
+    fn lp_token_conversion() {
+        // the limiting side decides the lp amount
+        assert_eq!(
+            ConstantProductCurve::trading_tokens_to_lp_tokens(1, 25, 10, 2, 49),
+            Some(5)
+        );
+        assert_eq!(
+            ConstantProductCurve::trading_tokens_to_lp_tokens(5, 10, 101, 100, 202),
+            Some(5)
+        );
+        assert_eq!(
+            ConstantProductCurve::trading_tokens_to_lp_tokens(1, 100, 10, 5, 501),
+            Some(1)
+        );
+        assert_eq!(
+            ConstantProductCurve::trading_tokens_to_lp_tokens(1, 1, 10, 0, 501),
+            None
+        );
+    }
+
+    #[test]
+    fn single_sided_swap_amount() {
+        // x0 = 100, a = 300: sqrt(100 * 400) - 100 = 100
+        assert_eq!(
+            ConstantProductCurve::single_sided_swap_amount(300, 100),
+            Some(100)
+        );
+        assert_eq!(
+            ConstantProductCurve::single_sided_swap_amount(0, 100),
+            Some(0)
+        );
+        assert!(ConstantProductCurve::single_sided_swap_amount(
+            u64::MAX as u128,
+            u64::MAX as u128
+        )
+        .is_some());
+    }
+
     #[test]
     fn fail_trading_token_conversion() {
         let results = ConstantProductCurve::lp_tokens_to_trading_tokens(
@@ -282,4 +356,65 @@ mod tests {
             );
         }
     }
+
+    proptest! {
+        #[test]
+        fn lp_tokens_from_trading_tokens_never_exceed_amounts(
+            token_0_amount in 1..u64::MAX,
+            token_1_amount in 1..u64::MAX,
+            lp_token_supply in 1..u64::MAX,
+            swap_token_0_amount in 1..u64::MAX,
+            swap_token_1_amount in 1..u64::MAX,
+        ) {
+            let lp_token_amount = ConstantProductCurve::trading_tokens_to_lp_tokens(
+                token_0_amount as u128,
+                token_1_amount as u128,
+                lp_token_supply as u128,
+                swap_token_0_amount as u128,
+                swap_token_1_amount as u128,
+            ).unwrap();
+            prop_assume!(lp_token_amount > 0);
+            let results = ConstantProductCurve::lp_tokens_to_trading_tokens(
+                lp_token_amount,
+                lp_token_supply as u128,
+                swap_token_0_amount as u128,
+                swap_token_1_amount as u128,
+                RoundDirection::Ceiling,
+            ).unwrap();
+            prop_assert!(results.token_0_amount <= token_0_amount as u128);
+            prop_assert!(results.token_1_amount <= token_1_amount as u128);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn single_sided_swap_keeps_pool_ratio(
+            source_amount in 1_000..u32::MAX as u64,
+            swap_source_amount in 1_000..u32::MAX as u64,
+            swap_destination_amount in 1_000..u32::MAX as u64,
+        ) {
+            let source_amount = source_amount as u128;
+            let swap_source_amount = swap_source_amount as u128;
+            let swap_destination_amount = swap_destination_amount as u128;
+            let swap_amount = ConstantProductCurve::single_sided_swap_amount(
+                source_amount,
+                swap_source_amount,
+            ).unwrap();
+            prop_assert!(swap_amount < source_amount);
+            let destination_amount_swapped = ConstantProductCurve::swap_base_input_without_fees(
+                swap_amount,
+                swap_source_amount,
+                swap_destination_amount,
+            );
+            prop_assume!(destination_amount_swapped > 0);
+            // (a - x) * (y0 - delta_y) ~= delta_y * (x0 + x), within the rounding of
+            // the square root and of the swapped amount
+            let left = (source_amount - swap_amount)
+                * (swap_destination_amount - destination_amount_swapped);
+            let right = destination_amount_swapped * (swap_source_amount + swap_amount);
+            let epsilon =
+                2 * (swap_source_amount + swap_amount + swap_destination_amount + source_amount);
+            prop_assert!(left.abs_diff(right) <= epsilon);
+        }
+    }
 }
@@ -50,6 +50,15 @@ impl Fees {
         )
     }
 
+    /// Calculate the referrer share of the trading fee in trading tokens
+    pub fn referral_fee(trade_fee: u128, referral_fee_rate: u64) -> Option<u128> {
+        floor_div(
+            trade_fee,
+            u128::from(referral_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
     pub fn calculate_pre_fee_amount(post_fee_amount: u128, trade_fee_rate: u64) -> Option<u128> {
         if trade_fee_rate == 0 {
             Some(post_fee_amount)
@@ -34,4 +34,26 @@ pub enum ErrorCode {
     SellResultNone,
     #[msg("Buy result is none")]
     BuyResultNone,
+    #[msg("Invalid merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Early access allocation exceeded")]
+    EarlyAccessAllocationExceeded,
+    #[msg("Launch buy cap exceeded")]
+    LaunchBuyCapExceeded,
+    #[msg("Invalid lock time")]
+    InvalidLockTime,
+    #[msg("Mint is denied")]
+    MintDenied,
+    #[msg("Flash swap is not repaid")]
+    FlashSwapNotRepaid,
+    #[msg("Limit order price not reached")]
+    LimitPriceNotReached,
+    #[msg("A config change is already pending")]
+    PendingConfigChangeExists,
+    #[msg("No config change is pending")]
+    NoPendingConfigChange,
+    #[msg("The pending config change is not effective yet")]
+    PendingConfigChangeNotEffective,
+    #[msg("Unknown pool state version")]
+    UnknownPoolStateVersion,
 }
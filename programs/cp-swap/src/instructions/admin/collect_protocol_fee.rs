@@ -9,8 +9,8 @@ use anchor_spl::token_interface::TokenAccount;
 
 #[derive(Accounts)]
 pub struct CollectProtocolFee<'info> {
-    /// Only admin or owner can collect fee now
-    #[account(constraint = (owner.key() == amm_config.protocol_owner || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner)]
+    /// Only the config owner can collect fee now
+    #[account(constraint = owner.key() == amm_config.protocol_owner @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -71,8 +71,8 @@ pub struct CollectProtocolFee<'info> {
     pub token_program_2022: Program<'info, Token2022>,
 }
 
-pub fn collect_protocol_fee(
-    ctx: Context<CollectProtocolFee>,
+pub fn collect_protocol_fee<'info>(
+    ctx: Context<'_, '_, '_, 'info, CollectProtocolFee<'info>>,
     amount_0_requested: u64,
     amount_1_requested: u64,
 ) -> Result<()> {
@@ -110,6 +110,7 @@ pub fn collect_protocol_fee(
         amount_0,
         ctx.accounts.vault_0_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_pool_vault_to_user(
@@ -125,7 +126,16 @@ pub fn collect_protocol_fee(
         amount_1,
         ctx.accounts.vault_1_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
+    emit!(ProtocolFeeCollectedEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        recipient_token_0_account: ctx.accounts.recipient_token_0_account.key(),
+        recipient_token_1_account: ctx.accounts.recipient_token_1_account.key(),
+        amount_0,
+        amount_1,
+    });
+
     Ok(())
 }
@@ -36,6 +36,7 @@ pub fn create_amm_config(
     token_0_lp_rate: u64,
     token_0_creator_rate: u64,
     token_1_creator_rate: u64,
+    create_pool_fee: u64,
 ) -> Result<()> {
     let amm_config = ctx.accounts.amm_config.deref_mut();
     amm_config.protocol_owner = crate::admin::id();
@@ -46,6 +47,8 @@ pub fn create_amm_config(
     amm_config.token_0_lp_rate = token_0_lp_rate;
     amm_config.token_0_creator_rate = token_0_creator_rate;
     amm_config.token_1_creator_rate = token_1_creator_rate;
+    amm_config.create_pool_fee = create_pool_fee;
     amm_config.fund_owner = ctx.accounts.owner.key();
+    amm_config.protocol_fee_rate = DEFAULT_PROTOCOL_FEE_RATE;
     Ok(())
 }
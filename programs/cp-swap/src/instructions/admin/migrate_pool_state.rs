@@ -0,0 +1,78 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct MigratePoolState<'info> {
+    /// Only admin can migrate pools, pays for the reallocation
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool state of any version, may be smaller than the current layout
+    #[account(
+        mut,
+        owner = crate::id()
+    )]
+    pub pool_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+    let pool_info = ctx.accounts.pool_state.to_account_info();
+    {
+        let data = pool_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == PoolState::DISCRIMINATOR,
+            ErrorCode::InvalidInput
+        );
+    }
+
+    // only the accounts of version 0, without `amm`, are smaller than the current layout
+    let original_layout = pool_info.data_len() < PoolState::LEN;
+    if original_layout {
+        require_eq!(
+            pool_info.data_len(),
+            POOL_STATE_V0_LEN,
+            ErrorCode::UnknownPoolStateVersion
+        );
+        let lamports = Rent::get()?
+            .minimum_balance(PoolState::LEN)
+            .saturating_sub(pool_info.lamports());
+        if lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.owner.to_account_info(),
+                        to: pool_info.clone(),
+                    },
+                ),
+                lamports,
+            )?;
+        }
+        pool_info.realloc(PoolState::LEN, true)?;
+    }
+
+    let mut data = pool_info.try_borrow_mut_data()?;
+    let pool_state: &mut PoolState = bytemuck::from_bytes_mut(&mut data[8..PoolState::LEN]);
+    // `version` reads zero in the accounts of 0 and 1
+    let old_version = match pool_state.version {
+        0 if original_layout => 0,
+        0 => 1,
+        version => version,
+    };
+    pool_state.migrate(old_version)?;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "pool_state migrated, old_version:{}, new_version:{}",
+        old_version,
+        POOL_STATE_VERSION
+    );
+    Ok(())
+}
@@ -12,3 +12,24 @@ pub use collect_protocol_fee::*;
 
 pub mod collect_fund_fee;
 pub use collect_fund_fee::*;
+
+pub mod update_global_status;
+pub use update_global_status::*;
+
+pub mod set_early_access;
+pub use set_early_access::*;
+
+pub mod set_launch_buy_cap;
+pub use set_launch_buy_cap::*;
+
+pub mod update_mint_registry;
+pub use update_mint_registry::*;
+
+pub mod migrate_pool_state;
+pub use migrate_pool_state::*;
+
+pub mod transfer_config_owner;
+pub use transfer_config_owner::*;
+
+pub mod pending_config;
+pub use pending_config::*;
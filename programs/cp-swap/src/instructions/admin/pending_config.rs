@@ -0,0 +1,61 @@
+use super::update_config::apply_config_change;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApplyPendingConfig<'info> {
+    /// Anyone can apply a pending change once it is effective
+    pub payer: Signer<'info>,
+
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn apply_pending_config(ctx: Context<ApplyPendingConfig>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    require!(
+        amm_config.has_pending_change(),
+        ErrorCode::NoPendingConfigChange
+    );
+    let block_timestamp =
+        u64::try_from(Clock::get()?.unix_timestamp).map_err(|_| ErrorCode::InvalidInput)?;
+    require_gte!(
+        block_timestamp,
+        amm_config.pending_effective_time,
+        ErrorCode::PendingConfigChangeNotEffective
+    );
+    let update = amm_config.pending_change().ok_or(ErrorCode::InvalidInput)?;
+    amm_config.clear_pending_change();
+    apply_config_change(amm_config, update)?;
+    emit!(ConfigChangeAppliedEvent {
+        amm_config: amm_config.key(),
+        update,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelPendingConfig<'info> {
+    /// The amm config owner
+    #[account(address = amm_config.protocol_owner @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn cancel_pending_config(ctx: Context<CancelPendingConfig>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    let update = amm_config
+        .pending_change()
+        .ok_or(ErrorCode::NoPendingConfigChange)?;
+    emit!(ConfigChangeCancelledEvent {
+        amm_config: amm_config.key(),
+        update,
+    });
+    amm_config.clear_pending_change();
+    Ok(())
+}
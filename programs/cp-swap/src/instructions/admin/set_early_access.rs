@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetEarlyAccess<'info> {
+    #[account(
+        address = pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_early_access(
+    ctx: Context<SetEarlyAccess>,
+    merkle_root: [u8; 32],
+    early_access_open_time: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // The allowlist can't be changed once the pool is open
+    if block_timestamp >= pool_state.open_time {
+        return err!(ErrorCode::NotApproved);
+    }
+    require_gt!(pool_state.open_time, early_access_open_time);
+    pool_state.early_access_root = merkle_root;
+    pool_state.early_access_open_time = early_access_open_time;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetLaunchBuyCap<'info> {
+    #[account(
+        address = pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn set_launch_buy_cap(
+    ctx: Context<SetLaunchBuyCap>,
+    launch_buy_cap_duration: u64,
+    max_buy_amount_0: u64,
+    max_buy_amount_1: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // The caps can't be changed once the pool is open
+    if block_timestamp >= pool_state.open_time {
+        return err!(ErrorCode::NotApproved);
+    }
+    pool_state.launch_buy_cap_duration = launch_buy_cap_duration;
+    pool_state.max_buy_amount_0 = max_buy_amount_0;
+    pool_state.max_buy_amount_1 = max_buy_amount_1;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
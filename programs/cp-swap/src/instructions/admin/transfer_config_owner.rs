@@ -0,0 +1,52 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TransferAmmConfigOwner<'info> {
+    /// The amm config owner
+    #[account(address = amm_config.protocol_owner @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn transfer_amm_config_owner(
+    ctx: Context<TransferAmmConfigOwner>,
+    new_owner: Pubkey,
+) -> Result<()> {
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, owner:{}, pending_owner:{}",
+        ctx.accounts.amm_config.protocol_owner.to_string(),
+        new_owner.to_string()
+    );
+    ctx.accounts.amm_config.pending_protocol_owner = new_owner;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAmmConfigOwner<'info> {
+    /// The pending owner of the amm config
+    #[account(address = amm_config.pending_protocol_owner @ ErrorCode::InvalidOwner)]
+    pub pending_owner: Signer<'info>,
+
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn accept_amm_config_owner(ctx: Context<AcceptAmmConfigOwner>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, old_owner:{}, new_owner:{}",
+        amm_config.protocol_owner.to_string(),
+        amm_config.pending_protocol_owner.to_string()
+    );
+    amm_config.protocol_owner = amm_config.pending_protocol_owner;
+    amm_config.pending_protocol_owner = Pubkey::default();
+    Ok(())
+}
@@ -4,8 +4,8 @@ use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct UpdateAmmConfig<'info> {
-    /// The amm config owner or admin
-    #[account(address = crate::admin::id() @ ErrorCode::InvalidOwner)]
+    /// The amm config owner
+    #[account(address = amm_config.protocol_owner @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// Amm config account to be changed
@@ -13,31 +13,47 @@ pub struct UpdateAmmConfig<'info> {
     pub amm_config: Account<'info, AmmConfig>,
 }
 
-pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
+pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, update: AmmConfigUpdate) -> Result<()> {
     let amm_config = &mut ctx.accounts.amm_config;
-    let match_param = Some(param);
-    match match_param {
-        Some(0) => {
-            let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
-            set_new_fund_owner(amm_config, new_fund_owner)?;
-        }
-        Some(1) => amm_config.token_1_lp_rate = value,
-        Some(2) => amm_config.token_0_lp_rate = value,
-        Some(3) => amm_config.token_0_creator_rate = value,
-        Some(4) => amm_config.token_1_creator_rate = value,
-        Some(5) => amm_config.disable_create_pool = if value == 0 { false } else { true },
-        _ => return err!(ErrorCode::InvalidInput),
+    if amm_config.update_delay == 0 {
+        return apply_config_change(amm_config, update);
     }
+    require!(
+        !amm_config.has_pending_change(),
+        ErrorCode::PendingConfigChangeExists
+    );
+    // Reject invalid changes now rather than when applied
+    amm_config.clone().apply(update)?;
+
+    let effective_time = u64::try_from(Clock::get()?.unix_timestamp)
+        .map_err(|_| ErrorCode::InvalidInput)?
+        .checked_add(u64::from(amm_config.update_delay))
+        .ok_or(ErrorCode::InvalidInput)?;
+    amm_config.set_pending_change(update, effective_time);
+    emit!(ConfigChangeQueuedEvent {
+        amm_config: amm_config.key(),
+        update,
+        effective_time,
+    });
     Ok(())
 }
-fn set_new_fund_owner(amm_config: &mut Account<AmmConfig>, new_fund_owner: Pubkey) -> Result<()> {
-    require_keys_neq!(new_fund_owner, Pubkey::default());
+
+pub fn apply_config_change(
+    amm_config: &mut Account<AmmConfig>,
+    update: AmmConfigUpdate,
+) -> Result<()> {
+    let old_value = amm_config.current(update);
+    amm_config.apply(update)?;
     #[cfg(feature = "enable-log")]
     msg!(
-        "amm_config, old_fund_owner:{}, new_fund_owner:{}",
-        amm_config.fund_owner.to_string(),
-        new_fund_owner.key().to_string()
+        "amm_config, old_value:{:?}, new_value:{:?}",
+        old_value,
+        update
     );
-    amm_config.fund_owner = new_fund_owner;
+    emit!(AmmConfigUpdatedEvent {
+        amm_config: amm_config.key(),
+        old_value,
+        new_value: update,
+    });
     Ok(())
 }
@@ -0,0 +1,31 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateGlobalStatus<'info> {
+    /// Only the config owner or admin can pause all pools of a config,
+    /// the admin keeps the incident pause of configs it doesn't own
+    #[account(
+        constraint = owner.key() == amm_config.protocol_owner
+            || owner.key() == crate::admin::id() @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn update_global_status(ctx: Context<UpdateGlobalStatus>, status: u8) -> Result<()> {
+    // only the bits of `PoolStatusBitIndex`, 0 to 4, are defined
+    require_gt!(1 << 5, status, ErrorCode::InvalidInput);
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amm_config, old_global_status:{}, new_global_status:{}",
+        ctx.accounts.amm_config.global_status,
+        status
+    );
+    ctx.accounts.amm_config.global_status = status;
+    Ok(())
+}
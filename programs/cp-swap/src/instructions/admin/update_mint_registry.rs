@@ -0,0 +1,71 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct SetMintRegistryEntry<'info> {
+    /// Only admin can manage the mint registry
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            mint.as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = MintRegistryEntry::LEN
+    )]
+    pub mint_registry_entry: Account<'info, MintRegistryEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_mint_registry_entry(
+    ctx: Context<SetMintRegistryEntry>,
+    mint: Pubkey,
+    kind: MintListKind,
+) -> Result<()> {
+    #[cfg(feature = "enable-log")]
+    msg!("mint_registry, mint:{}, kind:{:?}", mint, kind);
+    let mint_registry_entry = &mut ctx.accounts.mint_registry_entry;
+    mint_registry_entry.bump = ctx.bumps.mint_registry_entry;
+    mint_registry_entry.mint = mint;
+    mint_registry_entry.kind = kind;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RemoveMintRegistryEntry<'info> {
+    /// Only admin can manage the mint registry
+    #[account(
+        mut,
+        address = crate::admin::id() @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            mint.as_ref(),
+        ],
+        bump = mint_registry_entry.bump,
+        close = owner
+    )]
+    pub mint_registry_entry: Account<'info, MintRegistryEntry>,
+}
+
+pub fn remove_mint_registry_entry(
+    _ctx: Context<RemoveMintRegistryEntry>,
+    _mint: Pubkey,
+) -> Result<()> {
+    Ok(())
+}
@@ -16,7 +16,13 @@ pub struct UpdatePoolStatus<'info> {
 pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
     require_gte!(255, status);
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let old_status = pool_state.status;
     pool_state.set_status(status);
     pool_state.recent_epoch = Clock::get()?.epoch;
+    emit!(PoolStatusChangedEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        old_status,
+        new_status: status,
+    });
     Ok(())
 }
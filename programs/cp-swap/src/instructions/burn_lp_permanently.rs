@@ -0,0 +1,60 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct BurnLpPermanently<'info> {
+    /// The burner
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Owner lp token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool lp token mint
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn burn_lp_permanently(ctx: Context<BurnLpPermanently>, lp_amount: u64) -> Result<()> {
+    require_gt!(lp_amount, 0);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+
+    // `lp_supply` is kept, the liquidity of burned lp stays in the pool like the initial lock
+    pool_state.lp_burned_amount = pool_state.lp_burned_amount.checked_add(lp_amount).unwrap();
+    pool_state.lp_burner = ctx.accounts.owner.key();
+
+    emit!(LpBurnedEvent {
+        pool_id,
+        burner: ctx.accounts.owner.key(),
+        lp_amount,
+        lp_burned_amount: pool_state.lp_burned_amount,
+        lp_supply: pool_state.lp_supply,
+    });
+
+    token_burn(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        lp_amount,
+        &[],
+    )
+}
@@ -0,0 +1,78 @@
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CancelLimitOrder<'info> {
+    /// The order owner, receives the remaining input and the rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, owns the order vault
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        close = owner
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// Escrows the input token of the order
+    #[account(
+        mut,
+        address = limit_order.order_vault
+    )]
+    pub order_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account to receive the remaining input token
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint sold by the order, receives the transfer fees withheld in the order vault
+    #[account(
+        mut,
+        address = limit_order.input_mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn cancel_limit_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelLimitOrder<'info>>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        ctx.accounts.order_vault.amount,
+        ctx.accounts.input_token_mint.decimals,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    close_escrow_account(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        signer_seeds,
+    )
+}
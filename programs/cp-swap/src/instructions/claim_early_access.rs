@@ -0,0 +1,57 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::merkle::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClaimEarlyAccess<'info> {
+    /// The allowlisted wallet, pays for the allocation account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The pool with an early access allowlist
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the allocation of owner in the pool, claimed again when the allowlist changes
+    #[account(
+        init_if_needed,
+        seeds = [
+            EARLY_ACCESS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = EarlyAccessState::LEN
+    )]
+    pub early_access: Account<'info, EarlyAccessState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_early_access(
+    ctx: Context<ClaimEarlyAccess>,
+    allocation: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    if pool_state.early_access_root == [0u8; 32] {
+        return err!(ErrorCode::NotApproved);
+    }
+    if ctx.accounts.early_access.merkle_root == pool_state.early_access_root {
+        return err!(ErrorCode::NotApproved);
+    }
+    let leaf = allocation_leaf(&ctx.accounts.owner.key(), allocation);
+    if !verify_merkle_proof(&proof, &pool_state.early_access_root, leaf) {
+        return err!(ErrorCode::InvalidMerkleProof);
+    }
+
+    let early_access = &mut ctx.accounts.early_access;
+    early_access.bump = ctx.bumps.early_access;
+    early_access.pool_id = ctx.accounts.pool_state.key();
+    early_access.owner = ctx.accounts.owner.key();
+    early_access.allocation = allocation;
+    early_access.amount_used = 0;
+    early_access.merkle_root = pool_state.early_access_root;
+    Ok(())
+}
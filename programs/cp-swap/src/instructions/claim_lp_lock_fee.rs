@@ -0,0 +1,100 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ClaimLpLockFee<'info> {
+    /// The locker
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, owns the lock vault
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the lock amounts and schedule
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = lp_lock.pool_id == pool_state.key(),
+    )]
+    pub lp_lock: Box<Account<'info, LpLockState>>,
+
+    /// Escrows the locked lp
+    #[account(
+        mut,
+        address = lp_lock.lp_vault
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner lp token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool lp token mint
+    #[account(
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_lp_lock_fee(ctx: Context<ClaimLpLockFee>) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let lp_lock = &mut ctx.accounts.lp_lock;
+    let fee_lp_amount = lp_lock
+        .claimable_fee_lp_amount(
+            pool_state.lp_supply,
+            total_token_0_amount,
+            total_token_1_amount,
+        )
+        .ok_or(ErrorCode::ZeroTradingTokens)?;
+    require_gt!(fee_lp_amount, 0);
+    lp_lock.lp_amount = lp_lock.lp_amount.checked_sub(fee_lp_amount).unwrap();
+
+    #[cfg(feature = "enable-log")]
+    msg!("fee_lp_amount:{}", fee_lp_amount);
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.lp_vault.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        fee_lp_amount,
+        ctx.accounts.lp_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        &[],
+    )
+}
@@ -0,0 +1,36 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateLaunchBuyState<'info> {
+    /// The buyer wallet, pays for the account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The pool with a launch buy cap
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the amounts bought by owner during the launch window
+    #[account(
+        init,
+        seeds = [
+            LAUNCH_BUY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = LaunchBuyState::LEN
+    )]
+    pub launch_buy_state: Account<'info, LaunchBuyState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_launch_buy_state(ctx: Context<CreateLaunchBuyState>) -> Result<()> {
+    let launch_buy_state = &mut ctx.accounts.launch_buy_state;
+    launch_buy_state.bump = ctx.bumps.launch_buy_state;
+    launch_buy_state.pool_id = ctx.accounts.pool_state.key();
+    launch_buy_state.owner = ctx.accounts.owner.key();
+    Ok(())
+}
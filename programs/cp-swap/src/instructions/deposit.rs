@@ -1,5 +1,6 @@
 use crate::curve::CurveCalculator;
 use crate::curve::RoundDirection;
+use crate::curve::AMM;
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::token::*;
@@ -28,21 +29,23 @@ pub struct Deposit<'info> {
     #[account(mut,  token::authority = owner)]
     pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The payer's token account for token_0
+    /// The payer's token account for token_0, omitted to pay native SOL through
+    /// `temp_wsol_account`
     #[account(
         mut,
         token::mint = token_0_vault.mint,
         token::authority = owner
     )]
-    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_0_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// The payer's token account for token_1
+    /// The payer's token account for token_1, omitted to pay native SOL through
+    /// `temp_wsol_account`
     #[account(
         mut,
         token::mint = token_1_vault.mint,
         token::authority = owner
     )]
-    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_1_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     /// The address that holds pool tokens for token_0
     #[account(
@@ -82,33 +85,76 @@ pub struct Deposit<'info> {
         address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
     ]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
-}
 
-pub fn deposit(
-    ctx: Context<Deposit>,
-    lp_token_amount: u64,
-    maximum_token_0_amount: u64,
-    maximum_token_1_amount: u64,
-) -> Result<()> {
+    /// Amm config account stores the global status
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
 
-    let pool_id = ctx.accounts.pool_state.key();
-    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    /// Required with `temp_wsol_account`
+    pub system_program: Option<Program<'info, System>>,
+
+    /// CHECK: temporary WSOL account of owner, created and closed by the instruction when the
+    /// token account of the native mint is omitted, owner funds it so must be writable
+    #[account(
+        mut,
+        seeds = [
+            crate::TEMP_WSOL_SEED.as_bytes(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        constraint = owner.is_writable @ ErrorCode::InvalidInput
+    )]
+    pub temp_wsol_account: Option<UncheckedAccount<'info>>,
+}
 
+/// Result of a deposit or a withdraw, set as return data for the programs invoking it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LpChangeReturnData {
+    /// Lp minted by a deposit or burned by a withdraw
+    pub lp_amount: u64,
+    /// Token_0 transferred from the user on deposit, transfer fee included,
+    /// or received by the user on withdraw, transfer fee excluded
+    pub token_0_amount: u64,
+    /// Token_1 transferred from the user on deposit, transfer fee included,
+    /// or received by the user on withdraw, transfer fee excluded
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+}
+
+/// Cost of depositing `lp_token_amount`, see `deposit_cost`
+pub struct DepositCost {
+    /// Pool share of token_0 for the lp amount
+    pub token_0_amount: u64,
+    /// Pool share of token_1 for the lp amount
+    pub token_1_amount: u64,
+    /// Token_0 paid by the user, transfer fee included and scaled by the cost ratio
+    pub transfer_token_0_amount: u64,
+    pub transfer_token_0_fee: u64,
+    /// Token_1 paid by the user, transfer fee included and scaled by the cost ratio
+    pub transfer_token_1_amount: u64,
+    pub transfer_token_1_fee: u64,
+    /// The pool curve after buying the lp amount
+    pub amm: AMM,
+}
 
+/// Price a deposit of `lp_token_amount` into a pool holding `total_token_0_amount` and
+/// `total_token_1_amount`: the pool share of each token plus its transfer fee, scaled by
+/// the cost ratio of buying the lp amount on the pool curve.
+/// `transfer_fee_0` and `transfer_fee_1` return the inverse transfer fee of an amount.
+pub fn deposit_cost(
+    pool_state: &PoolState,
+    lp_token_amount: u64,
+    total_token_0_amount: u64,
+    total_token_1_amount: u64,
+    transfer_fee_0: impl Fn(u64) -> Result<u64>,
+    transfer_fee_1: impl Fn(u64) -> Result<u64>,
+) -> Result<DepositCost> {
     let mut amm = pool_state.amm;
-    let buy_result = amm.apply_buy(lp_token_amount.into());
-    if buy_result.is_none() {
-        return err!(ErrorCode::BuyResultNone);
-    }
-    let buy_result = buy_result.unwrap();
-    
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
-        return err!(ErrorCode::NotApproved);
-    }
-    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
-        ctx.accounts.token_0_vault.amount,
-        ctx.accounts.token_1_vault.amount,
-    );
+    let buy_result = amm
+        .apply_buy(lp_token_amount.into())
+        .ok_or(ErrorCode::BuyResultNone)?;
+
     let results = CurveCalculator::lp_tokens_to_trading_tokens(
         u128::from(lp_token_amount),
         u128::from(pool_state.lp_supply),
@@ -119,38 +165,119 @@ pub fn deposit(
     .ok_or(ErrorCode::ZeroTradingTokens)?;
 
     let token_0_amount = u64::try_from(results.token_0_amount).unwrap();
-    let (transfer_token_0_amount, transfer_token_0_fee) = {
-        let transfer_fee =
-            get_transfer_inverse_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
-        (
-            token_0_amount.checked_add(transfer_fee).unwrap(),
-            transfer_fee,
-        )
-    };
-
+    let transfer_token_0_fee = transfer_fee_0(token_0_amount)?;
     let token_1_amount = u64::try_from(results.token_1_amount).unwrap();
-    let (transfer_token_1_amount, transfer_token_1_fee) = {
-        let transfer_fee =
-            get_transfer_inverse_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
-        (
-            token_1_amount.checked_add(transfer_fee).unwrap(),
-            transfer_fee,
-        )
-    };
+    let transfer_token_1_fee = transfer_fee_1(token_1_amount)?;
 
     // Magick
     let cost_ratio = buy_result.sol_amount as f64 / Q32 as f64;
 
-    let transfer_token_0_amount = (transfer_token_0_amount as f64 * cost_ratio).ceil() as u64;
-    let transfer_token_1_amount = (transfer_token_1_amount as f64 * cost_ratio).ceil() as u64;
+    let transfer_token_0_amount = (token_0_amount.checked_add(transfer_token_0_fee).unwrap() as f64
+        * cost_ratio)
+        .ceil() as u64;
+    let transfer_token_1_amount = (token_1_amount.checked_add(transfer_token_1_fee).unwrap() as f64
+        * cost_ratio)
+        .ceil() as u64;
+
+    Ok(DepositCost {
+        token_0_amount,
+        token_1_amount,
+        transfer_token_0_amount,
+        transfer_token_0_fee,
+        transfer_token_1_amount,
+        transfer_token_1_fee,
+        amm,
+    })
+}
+
+/// The largest lp amount whose `deposit_cost` fits in `maximum_token_0_amount` and
+/// `maximum_token_1_amount`, 0 if none does.
+/// The cost grows with the lp amount so it is found by bisection.
+pub fn max_deposit_lp_amount(
+    pool_state: &PoolState,
+    total_token_0_amount: u64,
+    total_token_1_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    transfer_fee_0: impl Fn(u64) -> Result<u64>,
+    transfer_fee_1: impl Fn(u64) -> Result<u64>,
+) -> u64 {
+    let fits = |lp_token_amount: u64| {
+        deposit_cost(
+            pool_state,
+            lp_token_amount,
+            total_token_0_amount,
+            total_token_1_amount,
+            &transfer_fee_0,
+            &transfer_fee_1,
+        )
+        .is_ok_and(|cost| {
+            cost.transfer_token_0_amount <= maximum_token_0_amount
+                && cost.transfer_token_1_amount <= maximum_token_1_amount
+        })
+    };
+    // the curve can't sell more than its real reserves
+    let amm = pool_state.amm;
+    let (mut low, mut high) = (
+        0,
+        u64::try_from(amm.real_token_reserves).unwrap_or(u64::MAX),
+    );
+    while low < high {
+        let mid = high - (high - low) / 2;
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+pub fn deposit<'info>(
+    ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+    lp_token_amount: u64,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+) -> Result<LpChangeReturnData> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::Deposit,
+        ctx.accounts.amm_config.global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let vault_0_mint = ctx.accounts.vault_0_mint.to_account_info();
+    let vault_1_mint = ctx.accounts.vault_1_mint.to_account_info();
+    let DepositCost {
+        token_0_amount,
+        token_1_amount,
+        transfer_token_0_amount,
+        transfer_token_0_fee,
+        transfer_token_1_amount,
+        transfer_token_1_fee,
+        amm,
+    } = deposit_cost(
+        pool_state,
+        lp_token_amount,
+        total_token_0_amount,
+        total_token_1_amount,
+        |amount| get_transfer_inverse_fee(&vault_0_mint, amount),
+        |amount| get_transfer_inverse_fee(&vault_1_mint, amount),
+    )?;
     pool_state.amm = amm;
-    
+
     #[cfg(feature = "enable-log")]
     msg!(
-        "results.token_0_amount;{}, results.token_1_amount:{},transfer_token_0_amount:{},transfer_token_0_fee:{},
+        "token_0_amount;{}, token_1_amount:{},transfer_token_0_amount:{},transfer_token_0_fee:{},
             transfer_token_1_amount:{},transfer_token_1_fee:{}",
-        results.token_0_amount,
-        results.token_1_amount,
+        token_0_amount,
+        token_1_amount,
         transfer_token_0_amount,
         transfer_token_0_fee,
         transfer_token_1_amount,
@@ -175,9 +302,22 @@ pub fn deposit(
         return Err(ErrorCode::ExceededSlippage.into());
     }
 
+    // an omitted token account pays native SOL through the temporary WSOL account
+    let token_0_account = match ctx.accounts.token_0_account.as_ref() {
+        Some(token_0_account) => token_0_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.vault_0_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.token_program.to_account_info(),
+            transfer_token_0_amount,
+        )?,
+    };
     transfer_from_user_to_pool_vault(
         ctx.accounts.owner.to_account_info(),
-        ctx.accounts.token_0_account.to_account_info(),
+        token_0_account.clone(),
         ctx.accounts.token_0_vault.to_account_info(),
         ctx.accounts.vault_0_mint.to_account_info(),
         if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
@@ -187,11 +327,32 @@ pub fn deposit(
         },
         transfer_token_0_amount,
         ctx.accounts.vault_0_mint.decimals,
+        ctx.remaining_accounts,
     )?;
+    if ctx.accounts.token_0_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.owner.to_account_info(),
+            token_0_account,
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
 
+    // an omitted token account pays native SOL through the temporary WSOL account
+    let token_1_account = match ctx.accounts.token_1_account.as_ref() {
+        Some(token_1_account) => token_1_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.vault_1_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.token_program.to_account_info(),
+            transfer_token_1_amount,
+        )?,
+    };
     transfer_from_user_to_pool_vault(
         ctx.accounts.owner.to_account_info(),
-        ctx.accounts.token_1_account.to_account_info(),
+        token_1_account.clone(),
         ctx.accounts.token_1_vault.to_account_info(),
         ctx.accounts.vault_1_mint.to_account_info(),
         if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
@@ -201,7 +362,15 @@ pub fn deposit(
         },
         transfer_token_1_amount,
         ctx.accounts.vault_1_mint.decimals,
+        ctx.remaining_accounts,
     )?;
+    if ctx.accounts.token_1_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.owner.to_account_info(),
+            token_1_account,
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
 
     pool_state.lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).unwrap();
 
@@ -215,5 +384,47 @@ pub fn deposit(
     )?;
     pool_state.recent_epoch = Clock::get()?.epoch;
 
-    Ok(())
+    Ok(LpChangeReturnData {
+        lp_amount: lp_token_amount,
+        token_0_amount: transfer_token_0_amount,
+        token_1_amount: transfer_token_1_amount,
+        token_0_transfer_fee: transfer_token_0_fee,
+        token_1_transfer_fee: transfer_token_1_fee,
+    })
+}
+
+pub fn deposit_by_amounts<'info>(
+    ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+    maximum_token_0_amount: u64,
+    maximum_token_1_amount: u64,
+    minimum_lp_out: u64,
+) -> Result<LpChangeReturnData> {
+    let lp_token_amount = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        );
+        max_deposit_lp_amount(
+            &pool_state,
+            total_token_0_amount,
+            total_token_1_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            |amount| get_transfer_inverse_fee(&ctx.accounts.vault_0_mint.to_account_info(), amount),
+            |amount| get_transfer_inverse_fee(&ctx.accounts.vault_1_mint.to_account_info(), amount),
+        )
+    };
+    require_gt!(lp_token_amount, 0);
+    require_gte!(lp_token_amount, minimum_lp_out, ErrorCode::ExceededSlippage);
+
+    #[cfg(feature = "enable-log")]
+    msg!("deposit_by_amounts lp_token_amount:{}", lp_token_amount);
+
+    deposit(
+        ctx,
+        lp_token_amount,
+        maximum_token_0_amount,
+        maximum_token_1_amount,
+    )
 }
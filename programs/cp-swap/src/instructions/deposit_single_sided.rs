@@ -0,0 +1,402 @@
+use super::deposit::{deposit_cost, max_deposit_lp_amount, DepositCost};
+use crate::curve::CurveCalculator;
+use crate::curve::TradeDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct DepositSingleSided<'info> {
+    /// Pays to mint the position
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Owner lp tokan account
+    #[account(mut, token::authority = owner)]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer's token account for input token
+    #[account(
+        mut,
+        token::mint = input_vault.mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for input token
+    #[account(
+        mut,
+        constraint = input_vault.key() == pool_state.load()?.token_0_vault || input_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for the other token
+    #[account(
+        constraint = output_vault.key() == pool_state.load()?.token_0_vault || output_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of input token
+    #[account(
+        address = input_vault.mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Lp token mint
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The mint of the other token
+    #[account(
+        address = output_vault.mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: registry entry of the input mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            input_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub input_mint_registry_entry: UncheckedAccount<'info>,
+
+    /// CHECK: registry entry of the output mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            output_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub output_mint_registry_entry: UncheckedAccount<'info>,
+
+    /// The launch buys of owner, only required to buy a capped token during the launch window
+    #[account(
+        mut,
+        seeds = [
+            LAUNCH_BUY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = launch_buy_state.bump,
+    )]
+    pub launch_buy_state: Option<Box<Account<'info, LaunchBuyState>>>,
+}
+
+pub fn deposit_single_sided<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositSingleSided<'info>>,
+    amount_in: u64,
+    minimum_lp_out: u64,
+) -> Result<()> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    let global_status = ctx.accounts.amm_config.global_status;
+    if !pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Deposit, global_status)
+        || !pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Swap, global_status)
+        || block_timestamp < pool_state.open_time
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    if MintRegistryEntry::is_denied(&ctx.accounts.input_mint_registry_entry)?
+        || MintRegistryEntry::is_denied(&ctx.accounts.output_mint_registry_entry)?
+    {
+        return err!(ErrorCode::MintDenied);
+    }
+
+    let transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0);
+
+    let (trade_direction, total_input_token_amount, total_output_token_amount) =
+        if ctx.accounts.input_vault.key() == pool_state.token_0_vault
+            && ctx.accounts.output_vault.key() == pool_state.token_1_vault
+        {
+            let (total_input_token_amount, total_output_token_amount) = pool_state
+                .vault_amount_without_fee(
+                    ctx.accounts.input_vault.amount,
+                    ctx.accounts.output_vault.amount,
+                );
+
+            (
+                TradeDirection::ZeroForOne,
+                total_input_token_amount,
+                total_output_token_amount,
+            )
+        } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
+            && ctx.accounts.output_vault.key() == pool_state.token_0_vault
+        {
+            let (total_output_token_amount, total_input_token_amount) = pool_state
+                .vault_amount_without_fee(
+                    ctx.accounts.output_vault.amount,
+                    ctx.accounts.input_vault.amount,
+                );
+
+            (
+                TradeDirection::OneForZero,
+                total_input_token_amount,
+                total_output_token_amount,
+            )
+        } else {
+            return err!(ErrorCode::InvalidVault);
+        };
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::swap_direction(trade_direction),
+        global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    let max_buy_amount = pool_state.launch_max_buy_amount(trade_direction, block_timestamp);
+    if max_buy_amount > 0 && ctx.accounts.launch_buy_state.is_none() {
+        return err!(ErrorCode::NotApproved);
+    }
+
+    // Swap part of the input into the other token, the rest is deposited with the swap result
+    let constant_before = u128::from(total_input_token_amount)
+        .checked_mul(u128::from(total_output_token_amount))
+        .unwrap();
+    let (output_token_creator_rate, output_token_lp_rate) = match trade_direction {
+        TradeDirection::ZeroForOne => (
+            ctx.accounts.amm_config.token_0_creator_rate,
+            ctx.accounts.amm_config.token_0_lp_rate,
+        ),
+        TradeDirection::OneForZero => (
+            ctx.accounts.amm_config.token_1_creator_rate,
+            ctx.accounts.amm_config.token_1_lp_rate,
+        ),
+    };
+    let total_fee = output_token_creator_rate + output_token_lp_rate;
+    let protocol_fee = ctx.accounts.amm_config.protocol_fee(total_fee);
+
+    let swap_amount = CurveCalculator::single_sided_swap_amount(
+        u128::from(actual_amount_in),
+        u128::from(total_input_token_amount),
+        total_fee,
+    )
+    .ok_or(ErrorCode::ZeroTradingTokens)?;
+    let result = CurveCalculator::swap_base_input(
+        swap_amount,
+        u128::from(total_input_token_amount),
+        u128::from(total_output_token_amount),
+        total_fee,
+        protocol_fee,
+        output_token_creator_rate,
+    )
+    .ok_or(ErrorCode::ZeroTradingTokens)?;
+
+    let constant_after = result
+        .new_swap_source_amount
+        .checked_mul(result.new_swap_destination_amount)
+        .unwrap();
+    require_gte!(constant_after, constant_before);
+
+    if max_buy_amount > 0 {
+        let launch_buy_state = ctx.accounts.launch_buy_state.as_mut().unwrap();
+        if !launch_buy_state.buy(
+            trade_direction,
+            u64::try_from(result.destination_amount_swapped).unwrap(),
+            max_buy_amount,
+        ) {
+            return err!(ErrorCode::LaunchBuyCapExceeded);
+        }
+    }
+
+    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(result.creator_fee).unwrap();
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        }
+        TradeDirection::OneForZero => {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    };
+
+    // Deposit the rest of input with the swapped output at the ratio after the swap, priced
+    // on the pool curve like `deposit`. Both are already in the vaults so no transfer fee
+    // applies, any remainder stays in the pool
+    let new_total_input_token_amount = u64::try_from(result.new_swap_source_amount)
+        .unwrap()
+        .checked_sub(protocol_fee + fund_fee)
+        .unwrap();
+    let new_total_output_token_amount =
+        u64::try_from(result.new_swap_destination_amount).unwrap();
+    let deposit_input_amount = u64::try_from(u128::from(actual_amount_in) - swap_amount).unwrap();
+    let deposit_output_amount = u64::try_from(result.destination_amount_swapped).unwrap();
+    let (
+        total_token_0_amount,
+        total_token_1_amount,
+        maximum_token_0_amount,
+        maximum_token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+    ) = match trade_direction {
+        TradeDirection::ZeroForOne => (
+            new_total_input_token_amount,
+            new_total_output_token_amount,
+            deposit_input_amount,
+            deposit_output_amount,
+            transfer_fee,
+            0,
+        ),
+        TradeDirection::OneForZero => (
+            new_total_output_token_amount,
+            new_total_input_token_amount,
+            deposit_output_amount,
+            deposit_input_amount,
+            0,
+            transfer_fee,
+        ),
+    };
+    let lp_token_amount = max_deposit_lp_amount(
+        pool_state,
+        total_token_0_amount,
+        total_token_1_amount,
+        maximum_token_0_amount,
+        maximum_token_1_amount,
+        |_| Ok(0),
+        |_| Ok(0),
+    );
+    require_gt!(lp_token_amount, 0);
+    require_gte!(lp_token_amount, minimum_lp_out, ErrorCode::ExceededSlippage);
+    let DepositCost {
+        token_0_amount,
+        token_1_amount,
+        amm,
+        ..
+    } = deposit_cost(
+        pool_state,
+        lp_token_amount,
+        total_token_0_amount,
+        total_token_1_amount,
+        |_| Ok(0),
+        |_| Ok(0),
+    )?;
+    pool_state.amm = amm;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "swap_amount:{}, destination_amount_swapped:{}, token_0_amount:{}, token_1_amount:{}, lp_token_amount:{}",
+        swap_amount,
+        result.destination_amount_swapped,
+        token_0_amount,
+        token_1_amount,
+        lp_token_amount
+    );
+
+    emit!(SwapEvent {
+        pool_id,
+        input_vault_before: total_input_token_amount,
+        output_vault_before: total_output_token_amount,
+        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
+        output_amount: deposit_output_amount,
+        input_transfer_fee: transfer_fee,
+        output_transfer_fee: 0,
+        base_input: true,
+        referral_fee: 0,
+    });
+
+    emit!(LpChangeEvent {
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        change_type: 0
+    });
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
+    )?;
+
+    pool_state.lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).unwrap();
+    token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        lp_token_amount,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )?;
+
+    ctx.accounts.input_vault.reload()?;
+    // price interest-bearing tokens by their UI amount
+    let input_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_vault.amount,
+        block_timestamp as i64,
+    )?;
+    let output_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_vault.amount,
+        block_timestamp as i64,
+    )?;
+    let (token_0_price_x64, token_1_price_x64) = match trade_direction {
+        TradeDirection::ZeroForOne => pool_state.token_price_x32(input_vault_amount, output_vault_amount),
+        TradeDirection::OneForZero => pool_state.token_price_x32(output_vault_amount, input_vault_amount),
+    };
+    ctx.accounts.observation_state.load_mut()?.update(
+        oracle::block_timestamp(),
+        token_0_price_x64,
+        token_1_price_x64,
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    Ok(())
+}
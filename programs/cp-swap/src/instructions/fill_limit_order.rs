@@ -0,0 +1,407 @@
+use crate::curve::calculator::CurveCalculator;
+use crate::curve::TradeDirection;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct FillLimitOrder<'info> {
+    /// The keeper filling the order, receives the bounty
+    pub keeper: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, owns the order vault
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool in which the order will be filled
+    #[account(
+        mut,
+        address = limit_order.pool_id
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(mut)]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// CHECK: the order owner, receives the rent once the order is fully filled
+    #[account(
+        mut,
+        address = limit_order.owner
+    )]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Escrows the input token of the order
+    #[account(
+        mut,
+        address = limit_order.order_vault
+    )]
+    pub order_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account to receive the output token
+    #[account(
+        mut,
+        token::mint = output_token_mint,
+        token::authority = limit_order.owner
+    )]
+    pub owner_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The keeper's token account to receive the bounty in input token
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+    )]
+    pub keeper_input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for input token
+    #[account(
+        mut,
+        constraint = input_vault.key() == pool_state.load()?.token_0_vault || input_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(
+        mut,
+        constraint = output_vault.key() == pool_state.load()?.token_0_vault || output_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// SPL program for output token transfers
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint sold by the order, receives the transfer fees withheld in the order vault
+    #[account(
+        mut,
+        address = input_vault.mint,
+        constraint = input_token_mint.key() == limit_order.input_mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint bought by the order
+    #[account(
+        address = output_vault.mint,
+        constraint = output_token_mint.key() == limit_order.output_mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// CHECK: registry entry of the input mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            input_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub input_mint_registry_entry: UncheckedAccount<'info>,
+
+    /// CHECK: registry entry of the output mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            output_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub output_mint_registry_entry: UncheckedAccount<'info>,
+}
+
+/// Fill up to `amount_in` of the remaining input of an order, permissionless.
+/// The owner receives at least `target_price_x32` output per input consumed, bounty included.
+pub fn fill_limit_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, FillLimitOrder<'info>>,
+    amount_in: u64,
+) -> Result<()> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::Swap,
+        ctx.accounts.amm_config.global_status,
+    ) || block_timestamp < pool_state.open_time
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    if MintRegistryEntry::is_denied(&ctx.accounts.input_mint_registry_entry)?
+        || MintRegistryEntry::is_denied(&ctx.accounts.output_mint_registry_entry)?
+    {
+        return err!(ErrorCode::MintDenied);
+    }
+
+    let limit_order = &mut ctx.accounts.limit_order;
+    let amount_in = std::cmp::min(amount_in, limit_order.remaining_amount_in);
+    require_gt!(amount_in, 0);
+    let keeper_bounty = limit_order.keeper_bounty(amount_in);
+    let swap_amount_in = amount_in - keeper_bounty;
+
+    let transfer_fee = get_transfer_fee(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        swap_amount_in,
+    )?;
+    // Take transfer fees into account for actual amount transferred in
+    let actual_amount_in = swap_amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0);
+
+    // Calculate the trade amounts
+    let (trade_direction, total_input_token_amount, total_output_token_amount, input_price_x32) =
+        if ctx.accounts.input_vault.key() == pool_state.token_0_vault
+            && ctx.accounts.output_vault.key() == pool_state.token_1_vault
+        {
+            let (total_input_token_amount, total_output_token_amount) = pool_state
+                .vault_amount_without_fee(
+                    ctx.accounts.input_vault.amount,
+                    ctx.accounts.output_vault.amount,
+                );
+            let (token_0_price_x32, _) = pool_state.token_price_x32(
+                ctx.accounts.input_vault.amount,
+                ctx.accounts.output_vault.amount,
+            );
+
+            (
+                TradeDirection::ZeroForOne,
+                total_input_token_amount,
+                total_output_token_amount,
+                token_0_price_x32,
+            )
+        } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
+            && ctx.accounts.output_vault.key() == pool_state.token_0_vault
+        {
+            let (total_output_token_amount, total_input_token_amount) = pool_state
+                .vault_amount_without_fee(
+                    ctx.accounts.output_vault.amount,
+                    ctx.accounts.input_vault.amount,
+                );
+            let (_, token_1_price_x32) = pool_state.token_price_x32(
+                ctx.accounts.output_vault.amount,
+                ctx.accounts.input_vault.amount,
+            );
+
+            (
+                TradeDirection::OneForZero,
+                total_input_token_amount,
+                total_output_token_amount,
+                token_1_price_x32,
+            )
+        } else {
+            return err!(ErrorCode::InvalidVault);
+        };
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::swap_direction(trade_direction),
+        ctx.accounts.amm_config.global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    // Orders can't bypass the launch buy caps
+    if pool_state.launch_max_buy_amount(trade_direction, block_timestamp) > 0 {
+        return err!(ErrorCode::NotApproved);
+    }
+    // The pool price of the input token has to cross the target first
+    require_gte!(
+        input_price_x32,
+        limit_order.target_price_x32,
+        ErrorCode::LimitPriceNotReached
+    );
+
+    let constant_before = u128::from(total_input_token_amount)
+        .checked_mul(u128::from(total_output_token_amount))
+        .unwrap();
+    let (output_token_creator_rate, output_token_lp_rate) = match trade_direction {
+        TradeDirection::ZeroForOne => (
+            ctx.accounts.amm_config.token_0_creator_rate,
+            ctx.accounts.amm_config.token_0_lp_rate,
+        ),
+        TradeDirection::OneForZero => (
+            ctx.accounts.amm_config.token_1_creator_rate,
+            ctx.accounts.amm_config.token_1_lp_rate,
+        ),
+    };
+    let total_fee = output_token_creator_rate + output_token_lp_rate;
+    let protocol_fee = ctx.accounts.amm_config.protocol_fee(total_fee);
+
+    let result = CurveCalculator::swap_base_input(
+        u128::from(actual_amount_in),
+        u128::from(total_input_token_amount),
+        u128::from(total_output_token_amount),
+        total_fee,
+        protocol_fee,
+        output_token_creator_rate,
+    )
+    .ok_or(ErrorCode::ZeroTradingTokens)?;
+
+    let constant_after = result
+        .new_swap_source_amount
+        .checked_mul(result.new_swap_destination_amount)
+        .unwrap();
+    require_gte!(constant_after, constant_before);
+    require_eq!(
+        u64::try_from(result.source_amount_swapped).unwrap(),
+        actual_amount_in
+    );
+    let (output_transfer_amount, output_transfer_fee) = {
+        let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
+        let transfer_fee = get_transfer_fee(
+            &ctx.accounts.output_token_mint.to_account_info(),
+            amount_out,
+        )?;
+        (amount_out, transfer_fee)
+    };
+    let amount_received = output_transfer_amount
+        .checked_sub(output_transfer_fee)
+        .unwrap();
+    require_gt!(amount_received, 0);
+    if !limit_order.is_price_reached(amount_in, amount_received) {
+        return err!(ErrorCode::LimitPriceNotReached);
+    }
+    limit_order.fill(amount_in, amount_received);
+
+    let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
+    let fund_fee = u64::try_from(result.creator_fee).unwrap();
+
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_0 =
+                pool_state.fund_fees_token_0.checked_add(fund_fee).unwrap();
+        }
+        TradeDirection::OneForZero => {
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .unwrap();
+            pool_state.fund_fees_token_1 =
+                pool_state.fund_fees_token_1.checked_add(fund_fee).unwrap();
+        }
+    };
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amount_in:{}, keeper_bounty:{}, amount_received:{}, remaining_amount_in:{}",
+        amount_in,
+        keeper_bounty,
+        amount_received,
+        limit_order.remaining_amount_in
+    );
+    emit!(SwapEvent {
+        pool_id,
+        input_vault_before: total_input_token_amount,
+        output_vault_before: total_output_token_amount,
+        input_amount: u64::try_from(result.source_amount_swapped).unwrap(),
+        output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
+        input_transfer_fee: transfer_fee,
+        output_transfer_fee,
+        base_input: true,
+        referral_fee: 0,
+    });
+    emit!(LimitOrderFilledEvent {
+        limit_order: limit_order.key(),
+        pool_id,
+        keeper: ctx.accounts.keeper.key(),
+        amount_in,
+        amount_out: amount_received,
+        keeper_bounty,
+        remaining_amount_in: limit_order.remaining_amount_in,
+    });
+
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        swap_amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.owner_output_token_account.to_account_info(),
+        ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_token_program.to_account_info(),
+        output_transfer_amount,
+        ctx.accounts.output_token_mint.decimals,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.keeper_input_token_account.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        keeper_bounty,
+        ctx.accounts.input_token_mint.decimals,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    // A fully filled order closes with its escrow, the rent goes back to the owner
+    if ctx.accounts.limit_order.remaining_amount_in == 0 {
+        close_escrow_account(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.order_vault.to_account_info(),
+            ctx.accounts.input_token_mint.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        ctx.accounts
+            .limit_order
+            .close(ctx.accounts.owner.to_account_info())?;
+    }
+
+    ctx.accounts.input_vault.reload()?;
+    ctx.accounts.output_vault.reload()?;
+    // price interest-bearing tokens by their UI amount
+    let input_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_vault.amount,
+        block_timestamp as i64,
+    )?;
+    let output_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_vault.amount,
+        block_timestamp as i64,
+    )?;
+    let (token_0_price_x64, token_1_price_x64) = match trade_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.token_price_x32(input_vault_amount, output_vault_amount)
+        }
+        TradeDirection::OneForZero => {
+            pool_state.token_price_x32(output_vault_amount, input_vault_amount)
+        }
+    };
+    ctx.accounts.observation_state.load_mut()?.update(
+        oracle::block_timestamp(),
+        token_0_price_x64,
+        token_1_price_x64,
+    );
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+use crate::curve::Fees;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FlashSwap<'info> {
+    /// The borrower, signs for the repayment in the receiver program
+    pub payer: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read the flash fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool in which the flash swap will be performed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The payer's token account to receive the borrowed token_0
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer's token account to receive the borrowed token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: registry entry of the token_0 mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            vault_0_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_0_mint_registry_entry: UncheckedAccount<'info>,
+
+    /// CHECK: registry entry of the token_1 mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            vault_1_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_1_mint_registry_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the program invoked with the borrowed tokens, must repay the vaults before returning
+    #[account(
+        executable,
+        constraint = receiver_program.key() != crate::id() @ ErrorCode::InvalidInput
+    )]
+    pub receiver_program: UncheckedAccount<'info>,
+}
+
+/// Lend `amount_0` and `amount_1` out of the vaults and invoke the receiver program with `data`.
+/// The receiver gets `payer`, `token_0_account`, `token_1_account`, `token_0_vault`, `token_1_vault`
+/// followed by `remaining_accounts`, and must transfer back the borrowed amounts plus the flash fee.
+/// `remaining_accounts` also resolve the transfer hook extra accounts of the vault transfers.
+pub fn flash_swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, FlashSwap<'info>>,
+    amount_0: u64,
+    amount_1: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    require!(amount_0 > 0 || amount_1 > 0, ErrorCode::InvalidInput);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let (auth_bump, total_token_0_amount, total_token_1_amount) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        if !pool_state.get_status_by_bit_with_global(
+            PoolStatusBitIndex::Swap,
+            ctx.accounts.amm_config.global_status,
+        ) || block_timestamp < pool_state.open_time
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        if MintRegistryEntry::is_denied(&ctx.accounts.token_0_mint_registry_entry)?
+            || MintRegistryEntry::is_denied(&ctx.accounts.token_1_mint_registry_entry)?
+        {
+            return err!(ErrorCode::MintDenied);
+        }
+        let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        );
+        (
+            pool_state.auth_bump,
+            total_token_0_amount,
+            total_token_1_amount,
+        )
+    };
+    require_gte!(total_token_0_amount, amount_0);
+    require_gte!(total_token_1_amount, amount_1);
+
+    let flash_fee_rate = ctx.accounts.amm_config.flash_fee_rate;
+    let fee_0 =
+        u64::try_from(Fees::trading_fee(u128::from(amount_0), flash_fee_rate).unwrap()).unwrap();
+    let fee_1 =
+        u64::try_from(Fees::trading_fee(u128::from(amount_1), flash_fee_rate).unwrap()).unwrap();
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ctx.remaining_accounts,
+    )?;
+
+    let mut account_infos = vec![
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+    ];
+    account_infos.extend_from_slice(ctx.remaining_accounts);
+    let accounts = account_infos
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    account_infos.push(ctx.accounts.receiver_program.to_account_info());
+    invoke(
+        &Instruction {
+            program_id: ctx.accounts.receiver_program.key(),
+            accounts,
+            data,
+        },
+        &account_infos,
+    )?;
+
+    // The fees of the pool can't change during the invoke, the program is not reentrant
+    ctx.accounts.token_0_vault.reload()?;
+    ctx.accounts.token_1_vault.reload()?;
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    let (new_total_token_0_amount, new_total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    require_gte!(
+        new_total_token_0_amount,
+        total_token_0_amount.checked_add(fee_0).unwrap(),
+        ErrorCode::FlashSwapNotRepaid
+    );
+    require_gte!(
+        new_total_token_1_amount,
+        total_token_1_amount.checked_add(fee_1).unwrap(),
+        ErrorCode::FlashSwapNotRepaid
+    );
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "amount_0:{}, amount_1:{}, fee_0:{}, fee_1:{}",
+        amount_0,
+        amount_1,
+        fee_0,
+        fee_1
+    );
+    emit!(FlashSwapEvent {
+        pool_id,
+        receiver_program: ctx.accounts.receiver_program.key(),
+        amount_0,
+        amount_1,
+        fee_0,
+        fee_1,
+    });
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    Ok(())
+}
@@ -161,6 +161,30 @@ pub struct Initialize<'info> {
     pub rent: Sysvar<'info, Rent>,
     /// CHECK: This is not dangerous because we don't read or write from this account
     pub winna_winna_chickum_dinna: AccountInfo<'info>,
+    /// CHECK: receives the create pool fee in lamports
+    #[account(
+        mut,
+        address = amm_config.protocol_owner
+    )]
+    pub create_pool_fee_receiver: UncheckedAccount<'info>,
+    /// CHECK: registry entry of token_0_mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            token_0_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_0_mint_registry_entry: UncheckedAccount<'info>,
+    /// CHECK: registry entry of token_1_mint, may not be created yet
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_1_mint_registry_entry: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
@@ -258,14 +282,27 @@ pub fn initialize_metadata(
     create_metadata_accounts_v3(metadata_ctx, token_data, false, true, None)?;
     Ok(())
 }
-pub fn initialize(
-    ctx: Context<Initialize>,
+pub fn initialize<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
     init_amount_0: u64,
     init_amount_1: u64,
     mut open_time: u64,
 ) -> Result<()> {
-    if !(is_supported_mint(&ctx.accounts.token_0_mint).unwrap()
-        && is_supported_mint(&ctx.accounts.token_1_mint).unwrap())
+    let mint_extension_policy = ctx.accounts.amm_config.mint_extension_policy;
+    let token_0_mint_kind = MintRegistryEntry::load(&ctx.accounts.token_0_mint_registry_entry)?;
+    let token_1_mint_kind = MintRegistryEntry::load(&ctx.accounts.token_1_mint_registry_entry)?;
+    if !(is_supported_mint(
+        &ctx.accounts.token_0_mint,
+        mint_extension_policy,
+        token_0_mint_kind,
+    )
+    .unwrap()
+        && is_supported_mint(
+            &ctx.accounts.token_1_mint,
+            mint_extension_policy,
+            token_1_mint_kind,
+        )
+        .unwrap())
     {
         return err!(ErrorCode::NotSupportMint);
     }
@@ -273,6 +310,19 @@ pub fn initialize(
     if ctx.accounts.amm_config.disable_create_pool {
         return err!(ErrorCode::NotApproved);
     }
+    // charge the create pool fee
+    if ctx.accounts.amm_config.create_pool_fee != 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.create_pool_fee_receiver.to_account_info(),
+                },
+            ),
+            ctx.accounts.amm_config.create_pool_fee,
+        )?;
+    }
     let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
     if open_time <= block_timestamp {
         open_time = block_timestamp + 1;
@@ -360,6 +410,7 @@ pub fn initialize(
         ctx.accounts.token_0_program.to_account_info(),
         init_amount_0,
         ctx.accounts.token_0_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_user_to_pool_vault(
@@ -370,6 +421,7 @@ pub fn initialize(
         ctx.accounts.token_1_program.to_account_info(),
         init_amount_1,
         ctx.accounts.token_1_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     let token_0_vault =
@@ -398,7 +450,7 @@ pub fn initialize(
         .unwrap()
         .integer_sqrt()
         .as_u64();
-    let lock_lp_amount = 100;
+    let lock_lp_amount = LOCK_LP_AMOUNT;
     msg!(
         "liquidity:{}, lock_lp_amount:{}, vault_0_amount:{},vault_1_amount:{}",
         liquidity,
@@ -431,6 +483,21 @@ pub fn initialize(
         ctx.accounts.observation_state.key(),
     );
 
+    emit!(PoolCreatedEvent {
+        pool_id: ctx.accounts.pool_state.key(),
+        amm_config: ctx.accounts.amm_config.key(),
+        creator: ctx.accounts.creator.key(),
+        token_0_mint: ctx.accounts.token_0_mint.key(),
+        token_1_mint: ctx.accounts.token_1_mint.key(),
+        token_0_vault: ctx.accounts.token_0_vault.key(),
+        token_1_vault: ctx.accounts.token_1_vault.key(),
+        lp_mint: ctx.accounts.lp_mint.key(),
+        init_amount_0,
+        init_amount_1,
+        lp_supply: liquidity,
+        open_time,
+    });
+
     Ok(())
 }
 
@@ -0,0 +1,137 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct LockLp<'info> {
+    /// The locker, pays for the lock accounts
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, owns the lock vault
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the lock amounts and schedule
+    #[account(
+        init,
+        seeds = [
+            LP_LOCK_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &index.to_be_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = LpLockState::LEN
+    )]
+    pub lp_lock: Box<Account<'info, LpLockState>>,
+
+    /// Escrows the locked lp
+    #[account(
+        init,
+        seeds = [
+            LP_LOCK_VAULT_SEED.as_bytes(),
+            lp_lock.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        token::mint = lp_mint,
+        token::authority = authority,
+        token::token_program = token_program,
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner lp token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool lp token mint
+    #[account(
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn lock_lp(
+    ctx: Context<LockLp>,
+    index: u64,
+    lp_amount: u64,
+    start_time: u64,
+    end_time: u64,
+) -> Result<()> {
+    require_gt!(lp_amount, 0);
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    if end_time <= block_timestamp || start_time > end_time {
+        return err!(ErrorCode::InvalidLockTime);
+    }
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let liquidity = LpLockState::liquidity(
+        lp_amount,
+        pool_state.lp_supply,
+        total_token_0_amount,
+        total_token_1_amount,
+    )
+    .ok_or(ErrorCode::EmptySupply)?;
+    require_gt!(liquidity, 0);
+
+    let lp_lock = &mut ctx.accounts.lp_lock;
+    lp_lock.bump = ctx.bumps.lp_lock;
+    lp_lock.index = index;
+    lp_lock.pool_id = ctx.accounts.pool_state.key();
+    lp_lock.owner = ctx.accounts.owner.key();
+    lp_lock.lp_vault = ctx.accounts.lp_vault.key();
+    lp_lock.lp_amount = lp_amount;
+    lp_lock.total_liquidity = liquidity;
+    lp_lock.unlocked_liquidity = 0;
+    lp_lock.start_time = start_time;
+    lp_lock.end_time = end_time;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        ctx.accounts.lp_vault.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        lp_amount,
+        ctx.accounts.lp_mint.decimals,
+        &[],
+    )
+}
@@ -13,3 +13,42 @@ pub use admin::*;
 
 pub mod swap_base_output;
 pub use swap_base_output::*;
+
+pub mod claim_early_access;
+pub use claim_early_access::*;
+
+pub mod create_launch_buy_state;
+pub use create_launch_buy_state::*;
+
+pub mod deposit_single_sided;
+pub use deposit_single_sided::*;
+
+pub mod withdraw_single_sided;
+pub use withdraw_single_sided::*;
+
+pub mod lock_lp;
+pub use lock_lp::*;
+
+pub mod unlock_lp;
+pub use unlock_lp::*;
+
+pub mod claim_lp_lock_fee;
+pub use claim_lp_lock_fee::*;
+
+pub mod burn_lp_permanently;
+pub use burn_lp_permanently::*;
+
+pub mod swap_route;
+pub use swap_route::*;
+
+pub mod flash_swap;
+pub use flash_swap::*;
+
+pub mod place_limit_order;
+pub use place_limit_order::*;
+
+pub mod cancel_limit_order;
+pub use cancel_limit_order::*;
+
+pub mod fill_limit_order;
+pub use fill_limit_order::*;
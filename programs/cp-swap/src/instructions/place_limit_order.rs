@@ -0,0 +1,122 @@
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct PlaceLimitOrder<'info> {
+    /// The order owner, pays for the order accounts
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, owns the order vault
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the order amounts and target price
+    #[account(
+        init,
+        seeds = [
+            LIMIT_ORDER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &index.to_be_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = LimitOrderState::LEN
+    )]
+    pub limit_order: Box<Account<'info, LimitOrderState>>,
+
+    /// Escrows the input token of the order
+    #[account(
+        init,
+        seeds = [
+            LIMIT_ORDER_VAULT_SEED.as_bytes(),
+            limit_order.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        token::mint = input_token_mint,
+        token::authority = authority,
+        token::token_program = input_token_program,
+    )]
+    pub order_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account for input token
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint sold by the order
+    #[account(
+        constraint = input_token_mint.key() == pool_state.load()?.token_0_mint || input_token_mint.key() == pool_state.load()?.token_1_mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint bought by the order
+    #[account(
+        constraint = output_token_mint.key() == pool_state.load()?.token_0_mint || output_token_mint.key() == pool_state.load()?.token_1_mint,
+        constraint = output_token_mint.key() != input_token_mint.key() @ ErrorCode::InvalidInput
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program for input token transfers
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn place_limit_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, PlaceLimitOrder<'info>>,
+    index: u64,
+    amount_in: u64,
+    target_price_x32: u128,
+    keeper_bounty_rate: u64,
+) -> Result<()> {
+    require_gt!(target_price_x32, 0);
+    require_gt!(FEE_RATE_DENOMINATOR_VALUE, keeper_bounty_rate);
+    let transfer_fee =
+        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+    // Only the amount arriving in the escrow can be filled
+    let actual_amount_in = amount_in.saturating_sub(transfer_fee);
+    require_gt!(actual_amount_in, 0);
+
+    let limit_order = &mut ctx.accounts.limit_order;
+    limit_order.bump = ctx.bumps.limit_order;
+    limit_order.index = index;
+    limit_order.pool_id = ctx.accounts.pool_state.key();
+    limit_order.owner = ctx.accounts.owner.key();
+    limit_order.order_vault = ctx.accounts.order_vault.key();
+    limit_order.input_mint = ctx.accounts.input_token_mint.key();
+    limit_order.output_mint = ctx.accounts.output_token_mint.key();
+    limit_order.target_price_x32 = target_price_x32;
+    limit_order.keeper_bounty_rate = keeper_bounty_rate;
+    limit_order.amount_in = actual_amount_in;
+    limit_order.remaining_amount_in = actual_amount_in;
+    limit_order.filled_amount_out = 0;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.order_vault.to_account_info(),
+        ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
+    )
+}
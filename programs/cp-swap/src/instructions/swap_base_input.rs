@@ -1,4 +1,5 @@
 use crate::curve::calculator::CurveCalculator;
+use crate::curve::Fees;
 use crate::curve::TradeDirection;
 use crate::error::ErrorCode;
 use crate::states::*;
@@ -29,13 +30,15 @@ pub struct Swap<'info> {
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
 
-    /// The user token account for input token
+    /// The user token account for input token, omitted to pay native SOL through
+    /// `temp_wsol_account`
     #[account(mut)]
-    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub input_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// The user token account for output token
+    /// The user token account for output token, omitted to receive native SOL through
+    /// `temp_wsol_account`
     #[account(mut)]
-    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub output_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     /// The vault token account for input token
     #[account(
@@ -71,14 +74,132 @@ pub struct Swap<'info> {
     /// The program account for the most recent oracle observation
     #[account(mut, address = pool_state.load()?.observation_key)]
     pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// CHECK: registry entry of the input mint, may not be created yet. Optional so clients
+    /// built before the registry keep working, the mint is then not checked against it
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            input_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub input_mint_registry_entry: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: registry entry of the output mint, may not be created yet. Optional so clients
+    /// built before the registry keep working, the mint is then not checked against it
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_bytes(),
+            output_token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub output_mint_registry_entry: Option<UncheckedAccount<'info>>,
+
+    /// The allocation of payer, only required to swap before `open_time`
+    #[account(
+        mut,
+        seeds = [
+            EARLY_ACCESS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump = early_access.bump,
+    )]
+    pub early_access: Option<Box<Account<'info, EarlyAccessState>>>,
+
+    /// The launch buys of payer, only required to buy a capped token during the launch window
+    #[account(
+        mut,
+        seeds = [
+            LAUNCH_BUY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump = launch_buy_state.bump,
+    )]
+    pub launch_buy_state: Option<Box<Account<'info, LaunchBuyState>>>,
+
+    /// Required with `temp_wsol_account`
+    pub system_program: Option<Program<'info, System>>,
+
+    /// The referrer token account for input token, receives a share of the trading fee
+    #[account(
+        mut,
+        token::mint = input_token_mint,
+    )]
+    pub referrer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: temporary WSOL account of payer, created and closed by the swap when the user
+    /// token account of the native mint is omitted, payer funds it so must be writable
+    #[account(
+        mut,
+        seeds = [
+            crate::TEMP_WSOL_SEED.as_bytes(),
+            payer.key().as_ref(),
+        ],
+        bump,
+        constraint = payer.is_writable @ ErrorCode::InvalidInput
+    )]
+    pub temp_wsol_account: Option<UncheckedAccount<'info>>,
 }
 
-pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+/// Result of a swap, set as return data for the programs invoking the swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SwapReturnData {
+    /// Input token transferred from the user, transfer fee included
+    pub amount_in: u64,
+    /// Output token received by the user, transfer fee excluded
+    pub amount_out: u64,
+    /// Trading fee in input token
+    pub trade_fee: u64,
+    /// Part of the trading fee accrued to the protocol
+    pub protocol_fee: u64,
+    /// Part of the trading fee accrued to the fund
+    pub fund_fee: u64,
+    /// Part of the trading fee paid to the referrer
+    pub referral_fee: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+}
+
+/// Whether the registry entry of a swap mint denies it, an omitted entry doesn't
+pub(crate) fn is_mint_denied(mint_registry_entry: &Option<UncheckedAccount>) -> Result<bool> {
+    mint_registry_entry
+        .as_deref()
+        .map_or(Ok(false), MintRegistryEntry::is_denied)
+}
+
+pub fn swap_base_input<'info>(
+    ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<SwapReturnData> {
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
-        || block_timestamp < pool_state.open_time
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::Swap,
+        ctx.accounts.amm_config.global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    if is_mint_denied(&ctx.accounts.input_mint_registry_entry)?
+        || is_mint_denied(&ctx.accounts.output_mint_registry_entry)?
+    {
+        return err!(ErrorCode::MintDenied);
+    }
+    // Before open_time only allowlisted wallets can swap, within the allocation they claimed
+    // under the current allowlist
+    let early_access_swap = block_timestamp < pool_state.open_time;
+    let early_access_claimed = ctx
+        .accounts
+        .early_access
+        .as_ref()
+        .is_some_and(|early_access| early_access.merkle_root == pool_state.early_access_root);
+    if early_access_swap
+        && (!pool_state.is_early_access_active(block_timestamp) || !early_access_claimed)
     {
         return err!(ErrorCode::NotApproved);
     }
@@ -122,6 +243,16 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         } else {
             return err!(ErrorCode::InvalidVault);
         };
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::swap_direction(trade_direction),
+        ctx.accounts.amm_config.global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    let max_buy_amount = pool_state.launch_max_buy_amount(trade_direction, block_timestamp);
+    if max_buy_amount > 0 && ctx.accounts.launch_buy_state.is_none() {
+        return err!(ErrorCode::NotApproved);
+    }
     let constant_before = u128::from(total_input_token_amount)
         .checked_mul(u128::from(total_output_token_amount))
         .unwrap();
@@ -136,7 +267,7 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         ),
     };
     let total_fee = output_token_creator_rate + output_token_lp_rate;
-    let protocol_fee = total_fee / 10000 * 2;
+    let protocol_fee = ctx.accounts.amm_config.protocol_fee(total_fee);
 
     let result = CurveCalculator::swap_base_input(
         u128::from(actual_amount_in),
@@ -181,8 +312,41 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         (amount_out, transfer_fee)
     };
 
+    if early_access_swap {
+        let early_access = ctx.accounts.early_access.as_mut().unwrap();
+        if !early_access.consume(u64::try_from(result.destination_amount_swapped).unwrap()) {
+            return err!(ErrorCode::EarlyAccessAllocationExceeded);
+        }
+    }
+    if max_buy_amount > 0 {
+        let launch_buy_state = ctx.accounts.launch_buy_state.as_mut().unwrap();
+        if !launch_buy_state.buy(
+            trade_direction,
+            u64::try_from(result.destination_amount_swapped).unwrap(),
+            max_buy_amount,
+        ) {
+            return err!(ErrorCode::LaunchBuyCapExceeded);
+        }
+    }
+
     let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
     let fund_fee = u64::try_from(result.creator_fee).unwrap();
+    // The referrer share is taken from the liquidity providers' part of the trading fee,
+    // so the constant product still grows
+    let referral_fee = match ctx.accounts.referrer_token_account.as_ref() {
+        Some(_) => {
+            let lp_fee = result
+                .total_fees
+                .saturating_sub(result.protocol_fee + result.creator_fee);
+            let referral_fee = Fees::referral_fee(
+                result.total_fees,
+                ctx.accounts.amm_config.referral_fee_rate,
+            )
+            .unwrap();
+            u64::try_from(std::cmp::min(referral_fee, lp_fee)).unwrap()
+        }
+        None => 0,
+    };
 
     match trade_direction {
         TradeDirection::ZeroForOne => {
@@ -211,47 +375,110 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
         output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
         input_transfer_fee,
         output_transfer_fee,
-        base_input: true
+        base_input: true,
+        referral_fee,
     });
 
+    // an omitted user token account pays or receives native SOL through the temporary WSOL account
+    let input_token_account = match ctx.accounts.input_token_account.as_ref() {
+        Some(input_token_account) => input_token_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.input_token_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.input_token_program.to_account_info(),
+            input_transfer_amount,
+        )?,
+    };
+    let output_token_account = match ctx.accounts.output_token_account.as_ref() {
+        Some(output_token_account) => output_token_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.output_token_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.output_token_program.to_account_info(),
+            0,
+        )?,
+    };
+
     transfer_from_user_to_pool_vault(
         ctx.accounts.payer.to_account_info(),
-        ctx.accounts.input_token_account.to_account_info(),
+        input_token_account.clone(),
         ctx.accounts.input_vault.to_account_info(),
         ctx.accounts.input_token_mint.to_account_info(),
         ctx.accounts.input_token_program.to_account_info(),
         input_transfer_amount,
         ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.output_vault.to_account_info(),
-        ctx.accounts.output_token_account.to_account_info(),
+        output_token_account.clone(),
         ctx.accounts.output_token_mint.to_account_info(),
         ctx.accounts.output_token_program.to_account_info(),
         output_transfer_amount,
         ctx.accounts.output_token_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
+    if let Some(referrer_token_account) = ctx.accounts.referrer_token_account.as_ref() {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.input_vault.to_account_info(),
+            referrer_token_account.to_account_info(),
+            ctx.accounts.input_token_mint.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            referral_fee,
+            ctx.accounts.input_token_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    if ctx.accounts.input_token_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.payer.to_account_info(),
+            input_token_account,
+            ctx.accounts.input_token_program.to_account_info(),
+        )?;
+    }
+    if ctx.accounts.output_token_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.payer.to_account_info(),
+            output_token_account,
+            ctx.accounts.output_token_program.to_account_info(),
+        )?;
+    }
+
     ctx.accounts.input_vault.reload()?;
     ctx.accounts.output_vault.reload()?;
+    // price interest-bearing tokens by their UI amount
+    let input_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_vault.amount,
+        block_timestamp as i64,
+    )?;
+    let output_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_vault.amount,
+        block_timestamp as i64,
+    )?;
     let (token_0_price_x64, token_1_price_x64) = if ctx.accounts.input_vault.key()
         == pool_state.token_0_vault
         && ctx.accounts.output_vault.key() == pool_state.token_1_vault
     {
-        pool_state.token_price_x32(
-            ctx.accounts.input_vault.amount,
-            ctx.accounts.output_vault.amount,
-        )
+        pool_state.token_price_x32(input_vault_amount, output_vault_amount)
     } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
         && ctx.accounts.output_vault.key() == pool_state.token_0_vault
     {
-        pool_state.token_price_x32(
-            ctx.accounts.output_vault.amount,
-            ctx.accounts.input_vault.amount,
-        )
+        pool_state.token_price_x32(output_vault_amount, input_vault_amount)
     } else {
         return err!(ErrorCode::InvalidVault);
     };
@@ -263,5 +490,16 @@ pub fn swap_base_input(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u
     );
     pool_state.recent_epoch = Clock::get()?.epoch;
 
-    Ok(())
+    Ok(SwapReturnData {
+        amount_in: input_transfer_amount,
+        amount_out: output_transfer_amount
+            .checked_sub(output_transfer_fee)
+            .ok_or(ErrorCode::ZeroTradingTokens)?,
+        trade_fee: u64::try_from(result.total_fees).unwrap(),
+        protocol_fee,
+        fund_fee,
+        referral_fee,
+        input_transfer_fee,
+        output_transfer_fee,
+    })
 }
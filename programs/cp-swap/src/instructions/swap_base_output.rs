@@ -1,21 +1,40 @@
-use super::swap_base_input::Swap;
-use crate::curve::{calculator::CurveCalculator, TradeDirection};
+use super::swap_base_input::{is_mint_denied, Swap, SwapReturnData};
+use crate::curve::{calculator::CurveCalculator, Fees, TradeDirection};
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::token::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 
-pub fn swap_base_output(
-    ctx: Context<Swap>,
+pub fn swap_base_output<'info>(
+    ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
     max_amount_in: u64,
     amount_out_less_fee: u64,
-) -> Result<()> {
+) -> Result<SwapReturnData> {
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
-        || block_timestamp < pool_state.open_time
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::Swap,
+        ctx.accounts.amm_config.global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    if is_mint_denied(&ctx.accounts.input_mint_registry_entry)?
+        || is_mint_denied(&ctx.accounts.output_mint_registry_entry)?
+    {
+        return err!(ErrorCode::MintDenied);
+    }
+    // Before open_time only allowlisted wallets can swap, within the allocation they claimed
+    // under the current allowlist
+    let early_access_swap = block_timestamp < pool_state.open_time;
+    let early_access_claimed = ctx
+        .accounts
+        .early_access
+        .as_ref()
+        .is_some_and(|early_access| early_access.merkle_root == pool_state.early_access_root);
+    if early_access_swap
+        && (!pool_state.is_early_access_active(block_timestamp) || !early_access_claimed)
     {
         return err!(ErrorCode::NotApproved);
     }
@@ -58,6 +77,16 @@ pub fn swap_base_output(
         } else {
             return err!(ErrorCode::InvalidVault);
         };
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::swap_direction(trade_direction),
+        ctx.accounts.amm_config.global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    let max_buy_amount = pool_state.launch_max_buy_amount(trade_direction, block_timestamp);
+    if max_buy_amount > 0 && ctx.accounts.launch_buy_state.is_none() {
+        return err!(ErrorCode::NotApproved);
+    }
     let constant_before = u128::from(total_input_token_amount)
         .checked_mul(u128::from(total_output_token_amount))
         .unwrap();
@@ -72,7 +101,7 @@ pub fn swap_base_output(
         ),
     };
     let total_fee = input_token_creator_rate + input_token_lp_rate;
-    let protocol_fee = total_fee / 10000 * 2;
+    let protocol_fee = ctx.accounts.amm_config.protocol_fee(total_fee);
     let result = CurveCalculator::swap_base_output(
         u128::from(actual_amount_out),
         u128::from(total_input_token_amount),
@@ -119,8 +148,41 @@ pub fn swap_base_output(
     );
     let (output_transfer_amount, output_transfer_fee) = (actual_amount_out, out_transfer_fee);
 
+    if early_access_swap {
+        let early_access = ctx.accounts.early_access.as_mut().unwrap();
+        if !early_access.consume(u64::try_from(result.destination_amount_swapped).unwrap()) {
+            return err!(ErrorCode::EarlyAccessAllocationExceeded);
+        }
+    }
+    if max_buy_amount > 0 {
+        let launch_buy_state = ctx.accounts.launch_buy_state.as_mut().unwrap();
+        if !launch_buy_state.buy(
+            trade_direction,
+            u64::try_from(result.destination_amount_swapped).unwrap(),
+            max_buy_amount,
+        ) {
+            return err!(ErrorCode::LaunchBuyCapExceeded);
+        }
+    }
+
     let protocol_fee = u64::try_from(result.protocol_fee).unwrap();
     let fund_fee = u64::try_from(result.creator_fee).unwrap();
+    // The referrer share is taken from the liquidity providers' part of the trading fee,
+    // so the constant product still grows
+    let referral_fee = match ctx.accounts.referrer_token_account.as_ref() {
+        Some(_) => {
+            let lp_fee = result
+                .total_fees
+                .saturating_sub(result.protocol_fee + result.creator_fee);
+            let referral_fee = Fees::referral_fee(
+                result.total_fees,
+                ctx.accounts.amm_config.referral_fee_rate,
+            )
+            .unwrap();
+            u64::try_from(std::cmp::min(referral_fee, lp_fee)).unwrap()
+        }
+        None => 0,
+    };
 
     match trade_direction {
         TradeDirection::ZeroForOne => {
@@ -149,47 +211,110 @@ pub fn swap_base_output(
         output_amount: u64::try_from(result.destination_amount_swapped).unwrap(),
         input_transfer_fee,
         output_transfer_fee,
-        base_input: false
+        base_input: false,
+        referral_fee,
     });
 
+    // an omitted user token account pays or receives native SOL through the temporary WSOL account
+    let input_token_account = match ctx.accounts.input_token_account.as_ref() {
+        Some(input_token_account) => input_token_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.input_token_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.input_token_program.to_account_info(),
+            input_transfer_amount,
+        )?,
+    };
+    let output_token_account = match ctx.accounts.output_token_account.as_ref() {
+        Some(output_token_account) => output_token_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.output_token_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.output_token_program.to_account_info(),
+            0,
+        )?,
+    };
+
     transfer_from_user_to_pool_vault(
         ctx.accounts.payer.to_account_info(),
-        ctx.accounts.input_token_account.to_account_info(),
+        input_token_account.clone(),
         ctx.accounts.input_vault.to_account_info(),
         ctx.accounts.input_token_mint.to_account_info(),
         ctx.accounts.input_token_program.to_account_info(),
         input_transfer_amount,
         ctx.accounts.input_token_mint.decimals,
+        ctx.remaining_accounts,
     )?;
 
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.output_vault.to_account_info(),
-        ctx.accounts.output_token_account.to_account_info(),
+        output_token_account.clone(),
         ctx.accounts.output_token_mint.to_account_info(),
         ctx.accounts.output_token_program.to_account_info(),
         output_transfer_amount,
         ctx.accounts.output_token_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
     )?;
 
+    if let Some(referrer_token_account) = ctx.accounts.referrer_token_account.as_ref() {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.input_vault.to_account_info(),
+            referrer_token_account.to_account_info(),
+            ctx.accounts.input_token_mint.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            referral_fee,
+            ctx.accounts.input_token_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    if ctx.accounts.input_token_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.payer.to_account_info(),
+            input_token_account,
+            ctx.accounts.input_token_program.to_account_info(),
+        )?;
+    }
+    if ctx.accounts.output_token_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.payer.to_account_info(),
+            output_token_account,
+            ctx.accounts.output_token_program.to_account_info(),
+        )?;
+    }
+
     ctx.accounts.input_vault.reload()?;
     ctx.accounts.output_vault.reload()?;
+    // price interest-bearing tokens by their UI amount
+    let input_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.input_token_mint.to_account_info(),
+        ctx.accounts.input_vault.amount,
+        block_timestamp as i64,
+    )?;
+    let output_vault_amount = get_interest_bearing_amount(
+        &ctx.accounts.output_token_mint.to_account_info(),
+        ctx.accounts.output_vault.amount,
+        block_timestamp as i64,
+    )?;
     let (token_0_price_x64, token_1_price_x64) = if ctx.accounts.input_vault.key()
         == pool_state.token_0_vault
         && ctx.accounts.output_vault.key() == pool_state.token_1_vault
     {
-        pool_state.token_price_x32(
-            ctx.accounts.input_vault.amount,
-            ctx.accounts.output_vault.amount,
-        )
+        pool_state.token_price_x32(input_vault_amount, output_vault_amount)
     } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
         && ctx.accounts.output_vault.key() == pool_state.token_0_vault
     {
-        pool_state.token_price_x32(
-            ctx.accounts.output_vault.amount,
-            ctx.accounts.input_vault.amount,
-        )
+        pool_state.token_price_x32(output_vault_amount, input_vault_amount)
     } else {
         return err!(ErrorCode::InvalidVault);
     };
@@ -201,5 +326,16 @@ pub fn swap_base_output(
     );
     pool_state.recent_epoch = Clock::get()?.epoch;
 
-    Ok(())
+    Ok(SwapReturnData {
+        amount_in: input_transfer_amount,
+        amount_out: output_transfer_amount
+            .checked_sub(output_transfer_fee)
+            .ok_or(ErrorCode::ZeroTradingTokens)?,
+        trade_fee: u64::try_from(result.total_fees).unwrap(),
+        protocol_fee,
+        fund_fee,
+        referral_fee,
+        input_transfer_fee,
+        output_transfer_fee,
+    })
 }
@@ -0,0 +1,94 @@
+use crate::error::ErrorCode;
+use crate::instructions::swap_base_input::*;
+use anchor_lang::prelude::*;
+use std::collections::BTreeSet;
+
+/// Minimum number of accounts of a hop in `remaining_accounts`, the accounts of `Swap` up to
+/// `observation_state` in the same order. They may be followed by its optional accounts,
+/// program id for the omitted ones, then by the transfer hook extra accounts of the hop
+pub const SWAP_ROUTE_HOP_ACCOUNTS_LEN: usize = 13;
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    /// The user performing the swaps, the payer of every hop
+    pub payer: Signer<'info>,
+}
+
+pub fn swap_route<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    hop_accounts_lens: Vec<u8>,
+) -> Result<()> {
+    let hop_count = hop_accounts_lens.len();
+    if hop_count == 0
+        || hop_accounts_lens
+            .iter()
+            .any(|len| usize::from(*len) < SWAP_ROUTE_HOP_ACCOUNTS_LEN)
+        || hop_accounts_lens
+            .iter()
+            .map(|len| usize::from(*len))
+            .sum::<usize>()
+            != ctx.remaining_accounts.len()
+    {
+        return err!(ErrorCode::InvalidInput);
+    }
+
+    let mut amount_in = amount_in;
+    let mut previous_output_token_account: Option<Pubkey> = None;
+    let mut remaining_accounts = ctx.remaining_accounts;
+    for (index, hop_accounts_len) in hop_accounts_lens.into_iter().enumerate() {
+        let (mut hop_accounts, next_hop_accounts) =
+            remaining_accounts.split_at(usize::from(hop_accounts_len));
+        remaining_accounts = next_hop_accounts;
+        let mut bumps = SwapBumps::default();
+        let mut accounts = Swap::try_accounts(
+            ctx.program_id,
+            &mut hop_accounts,
+            &[],
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+        require_keys_eq!(accounts.payer.key(), ctx.accounts.payer.key());
+        // hops have no temporary WSOL account, so they can't pay or receive native SOL
+        let input_token_account = accounts
+            .input_token_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?
+            .key();
+        let output_token_account = accounts
+            .output_token_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidInput)?
+            .key();
+        // the output of a hop is the input of the next one
+        if let Some(previous_output_token_account) = previous_output_token_account {
+            require_keys_eq!(input_token_account, previous_output_token_account);
+        }
+        previous_output_token_account = Some(output_token_account);
+
+        // only the final hop is guarded, intermediate hops just need a non zero output
+        let hop_minimum_amount_out = if index == hop_count - 1 {
+            minimum_amount_out
+        } else {
+            0
+        };
+        // what the accounts of `Swap` left are the transfer hook extra accounts of the hop
+        amount_in = swap_base_input(
+            Context::new(ctx.program_id, &mut accounts, hop_accounts, bumps),
+            amount_in,
+            hop_minimum_amount_out,
+        )?
+        .amount_out;
+        accounts.exit(ctx.program_id)?;
+    }
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "swap_route hop_count:{}, amount_out:{}",
+        hop_count,
+        amount_in
+    );
+
+    Ok(())
+}
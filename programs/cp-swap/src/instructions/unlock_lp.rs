@@ -0,0 +1,103 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    /// The locker, receives the rent of the lock accounts once fully unlocked
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, owns the lock vault
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Stores the lock amounts and schedule
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = lp_lock.pool_id == pool_state.key(),
+    )]
+    pub lp_lock: Box<Account<'info, LpLockState>>,
+
+    /// Escrows the locked lp
+    #[account(
+        mut,
+        address = lp_lock.lp_vault
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner lp token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool lp token mint
+    #[account(
+        address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
+    ]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let lp_lock = &mut ctx.accounts.lp_lock;
+    let (lp_amount, liquidity) = lp_lock.unlockable(block_timestamp);
+    if liquidity == 0 {
+        return err!(ErrorCode::InvalidLockTime);
+    }
+    lp_lock.lp_amount = lp_lock.lp_amount.checked_sub(lp_amount).unwrap();
+    lp_lock.unlocked_liquidity = lp_lock.unlocked_liquidity.checked_add(liquidity).unwrap();
+    // Nothing is locked anymore, the escrow is emptied, lp sent to it included, and closed
+    // with the lock
+    let fully_unlocked = lp_lock.lp_amount == 0;
+    let transfer_amount = if fully_unlocked {
+        ctx.accounts.lp_vault.amount
+    } else {
+        lp_amount
+    };
+
+    let auth_bump = ctx.accounts.pool_state.load()?.auth_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.lp_vault.to_account_info(),
+        ctx.accounts.owner_lp_token.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        transfer_amount,
+        ctx.accounts.lp_mint.decimals,
+        signer_seeds,
+        &[],
+    )?;
+    if fully_unlocked {
+        close_token_account(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.lp_vault.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+        ctx.accounts
+            .lp_lock
+            .close(ctx.accounts.owner.to_account_info())?;
+    }
+    Ok(())
+}
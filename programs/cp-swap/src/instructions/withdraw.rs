@@ -1,5 +1,7 @@
+use super::deposit::LpChangeReturnData;
 use crate::curve::CurveCalculator;
 use crate::curve::RoundDirection;
+use crate::curve::AMM;
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::token::*;
@@ -32,21 +34,23 @@ pub struct Withdraw<'info> {
     )]
     pub owner_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The owner's token account for receive token_0
+    /// The owner's token account for receive token_0, omitted to receive native SOL through
+    /// `temp_wsol_account`
     #[account(
         mut,
         token::mint = token_0_vault.mint,
         token::authority = owner
     )]
-    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_0_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    /// The owner's token account for receive token_1
+    /// The owner's token account for receive token_1, omitted to receive native SOL through
+    /// `temp_wsol_account`
     #[account(
         mut,
         token::mint = token_1_vault.mint,
         token::authority = owner
     )]
-    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_1_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     /// The address that holds pool tokens for token_0
     #[account(
@@ -93,24 +97,56 @@ pub struct Withdraw<'info> {
         address = spl_memo::id()
     )]
     pub memo_program: UncheckedAccount<'info>,
+
+    /// Amm config account stores the global status
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Required with `temp_wsol_account`
+    pub system_program: Option<Program<'info, System>>,
+
+    /// CHECK: temporary WSOL account of owner, created and closed by the instruction when the
+    /// token account of the native mint is omitted, owner funds it so must be writable
+    #[account(
+        mut,
+        seeds = [
+            crate::TEMP_WSOL_SEED.as_bytes(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        constraint = owner.is_writable @ ErrorCode::InvalidInput
+    )]
+    pub temp_wsol_account: Option<UncheckedAccount<'info>>,
+}
+
+/// Amounts of a withdraw of `lp_token_amount`, see `withdraw_amounts`
+pub struct WithdrawAmounts {
+    /// Pool share of token_0 for the lp amount
+    pub token_0_amount: u64,
+    /// Pool share of token_1 for the lp amount
+    pub token_1_amount: u64,
+    /// Token_0 received by the user, transfer fee excluded and scaled by the cost ratio
+    pub receive_token_0_amount: u64,
+    pub token_0_transfer_fee: u64,
+    /// Token_1 received by the user, transfer fee excluded and scaled by the cost ratio
+    pub receive_token_1_amount: u64,
+    pub token_1_transfer_fee: u64,
+    /// The pool curve after selling the lp amount
+    pub amm: AMM,
 }
 
-pub fn withdraw(
-    ctx: Context<Withdraw>,
+/// Price a withdraw of `lp_token_amount` from a pool holding `total_token_0_amount` and
+/// `total_token_1_amount`: the pool share of each token less its transfer fee, scaled by
+/// the cost ratio of selling the lp amount on the pool curve.
+/// `transfer_fee_0` and `transfer_fee_1` return the transfer fee of an amount.
+pub fn withdraw_amounts(
+    pool_state: &PoolState,
     lp_token_amount: u64,
-    minimum_token_0_amount: u64,
-    minimum_token_1_amount: u64,
-) -> Result<()> {
-    require_gt!(ctx.accounts.lp_mint.supply, 0);
-    let pool_id = ctx.accounts.pool_state.key();
-    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
-        return err!(ErrorCode::NotApproved);
-    }
-    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
-        ctx.accounts.token_0_vault.amount,
-        ctx.accounts.token_1_vault.amount,
-    );
+    total_token_0_amount: u64,
+    total_token_1_amount: u64,
+    transfer_fee_0: impl Fn(u64) -> Result<u64>,
+    transfer_fee_1: impl Fn(u64) -> Result<u64>,
+) -> Result<WithdrawAmounts> {
     let results = CurveCalculator::lp_tokens_to_trading_tokens(
         u128::from(lp_token_amount),
         u128::from(pool_state.lp_supply),
@@ -122,47 +158,79 @@ pub fn withdraw(
 
     let token_0_amount = u64::try_from(results.token_0_amount).unwrap();
     let token_0_amount = std::cmp::min(total_token_0_amount, token_0_amount);
-    let (receive_token_0_amount, token_0_transfer_fee) = {
-        let transfer_fee = get_transfer_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
-        (
-            token_0_amount.checked_sub(transfer_fee).unwrap(),
-            transfer_fee,
-        )
-    };
-
+    let token_0_transfer_fee = transfer_fee_0(token_0_amount)?;
     let token_1_amount = u64::try_from(results.token_1_amount).unwrap();
     let token_1_amount = std::cmp::min(total_token_1_amount, token_1_amount);
-    let (receive_token_1_amount, token_1_transfer_fee) = {
-        let transfer_fee = get_transfer_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
-        (
-            token_1_amount.checked_sub(transfer_fee).unwrap(),
-            transfer_fee,
-        )
-    };
+    let token_1_transfer_fee = transfer_fee_1(token_1_amount)?;
+
     let mut amm = pool_state.amm;
-    let sell_result = amm.apply_sell(lp_token_amount as u128);
-    if sell_result.is_none() {
-        return err!(ErrorCode::SellResultNone);
-    }
-    let sell_result = sell_result.unwrap();
-    
-    // Magick
+    let sell_result = amm
+        .apply_sell(lp_token_amount.into())
+        .ok_or(ErrorCode::SellResultNone)?;
 
+    // Magick
     let cost_ratio = sell_result.sol_amount as f64 / Q32 as f64;
 
-    let receive_token_0_amount = (receive_token_0_amount as f64 * cost_ratio).ceil() as u64;
-    let receive_token_1_amount = (receive_token_1_amount as f64 * cost_ratio).ceil() as u64;
-    
+    let receive_token_0_amount = (token_0_amount.checked_sub(token_0_transfer_fee).unwrap() as f64
+        * cost_ratio)
+        .ceil() as u64;
+    let receive_token_1_amount = (token_1_amount.checked_sub(token_1_transfer_fee).unwrap() as f64
+        * cost_ratio)
+        .ceil() as u64;
+
+    Ok(WithdrawAmounts {
+        token_0_amount,
+        token_1_amount,
+        receive_token_0_amount,
+        token_0_transfer_fee,
+        receive_token_1_amount,
+        token_1_transfer_fee,
+        amm,
+    })
+}
+
+pub fn withdraw<'info>(
+    ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+    lp_token_amount: u64,
+    minimum_token_0_amount: u64,
+    minimum_token_1_amount: u64,
+) -> Result<LpChangeReturnData> {
+    require_gt!(ctx.accounts.lp_mint.supply, 0);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit_with_global(
+        PoolStatusBitIndex::Withdraw,
+        ctx.accounts.amm_config.global_status,
+    ) {
+        return err!(ErrorCode::NotApproved);
+    }
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    );
+    let vault_0_mint = ctx.accounts.vault_0_mint.to_account_info();
+    let vault_1_mint = ctx.accounts.vault_1_mint.to_account_info();
+    let WithdrawAmounts {
+        receive_token_0_amount,
+        token_0_transfer_fee,
+        receive_token_1_amount,
+        token_1_transfer_fee,
+        amm,
+        ..
+    } = withdraw_amounts(
+        pool_state,
+        lp_token_amount,
+        total_token_0_amount,
+        total_token_1_amount,
+        |amount| get_transfer_fee(&vault_0_mint, amount),
+        |amount| get_transfer_fee(&vault_1_mint, amount),
+    )?;
     pool_state.amm = amm;
-    
-    
 
     #[cfg(feature = "enable-log")]
     msg!(
-        "results.token_0_amount;{}, results.token_1_amount:{},receive_token_0_amount:{},token_0_transfer_fee:{},
+        "receive_token_0_amount:{},token_0_transfer_fee:{},
             receive_token_1_amount:{},token_1_transfer_fee:{}",
-        results.token_0_amount,
-        results.token_1_amount,
         receive_token_0_amount,
         token_0_transfer_fee,
         receive_token_1_amount,
@@ -196,10 +264,23 @@ pub fn withdraw(
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
     )?;
 
+    // an omitted token account receives native SOL through the temporary WSOL account
+    let token_0_account = match ctx.accounts.token_0_account.as_ref() {
+        Some(token_0_account) => token_0_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.vault_0_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.token_program.to_account_info(),
+            0,
+        )?,
+    };
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.token_0_vault.to_account_info(),
-        ctx.accounts.token_0_account.to_account_info(),
+        token_0_account.clone(),
         ctx.accounts.vault_0_mint.to_account_info(),
         if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
             ctx.accounts.token_program.to_account_info()
@@ -209,12 +290,33 @@ pub fn withdraw(
         receive_token_0_amount,
         ctx.accounts.vault_0_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
     )?;
+    if ctx.accounts.token_0_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.owner.to_account_info(),
+            token_0_account,
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
 
+    // an omitted token account receives native SOL through the temporary WSOL account
+    let token_1_account = match ctx.accounts.token_1_account.as_ref() {
+        Some(token_1_account) => token_1_account.to_account_info(),
+        None => create_temp_wsol_account(
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.vault_1_mint.to_account_info(),
+            ctx.accounts.temp_wsol_account.as_ref(),
+            ctx.bumps.temp_wsol_account,
+            ctx.accounts.system_program.as_ref(),
+            &ctx.accounts.token_program.to_account_info(),
+            0,
+        )?,
+    };
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.token_1_vault.to_account_info(),
-        ctx.accounts.token_1_account.to_account_info(),
+        token_1_account.clone(),
         ctx.accounts.vault_1_mint.to_account_info(),
         if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
             ctx.accounts.token_program.to_account_info()
@@ -224,8 +326,23 @@ pub fn withdraw(
         receive_token_1_amount,
         ctx.accounts.vault_1_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+        ctx.remaining_accounts,
     )?;
+    if ctx.accounts.token_1_account.is_none() {
+        unwrap_sol(
+            ctx.accounts.owner.to_account_info(),
+            token_1_account,
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
     pool_state.recent_epoch = Clock::get()?.epoch;
 
-    Ok(())
+    Ok(LpChangeReturnData {
+        lp_amount: lp_token_amount,
+        token_0_amount: receive_token_0_amount,
+        token_1_amount: receive_token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+    })
 }
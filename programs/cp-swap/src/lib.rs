@@ -4,8 +4,12 @@ pub mod instructions;
 pub mod states;
 pub mod utils;
 
+#[cfg(feature = "cpi")]
+pub mod cpi_helpers;
+
 use anchor_lang::prelude::*;
 use instructions::*;
+use states::{AmmConfigUpdate, MintListKind};
 
 #[cfg(not(feature = "no-entrypoint"))]
 solana_security_txt::security_txt! {
@@ -32,6 +36,9 @@ pub mod admin {
 }
 
 pub const AUTH_SEED: &str = "vault_and_lp_mint_auth_seed";
+/// Seed of the temporary WSOL account of a user, `[TEMP_WSOL_SEED, user]`, created and closed
+/// within an instruction paying or receiving native SOL
+pub const TEMP_WSOL_SEED: &str = "temp_wsol";
 
 #[program]
 pub mod raydium_cp_swap {
@@ -42,43 +49,64 @@ pub mod raydium_cp_swap {
     ///
     /// * `ctx`- The accounts needed by instruction.
     /// * `index` - The index of amm config, there may be multiple config.
-    /// * `trade_fee_rate` - Trade fee rate, can be changed.
-    /// * `protocol_fee_rate` - The rate of protocol fee within tarde fee.
-    /// * `fund_fee_rate` - The rate of fund fee within tarde fee.
+    /// * `token_1_lp_rate` - Lp fee rate of swaps paying token_1, can be changed.
+    /// * `token_0_lp_rate` - Lp fee rate of swaps paying token_0, can be changed.
+    /// * `token_0_creator_rate` - Creator fee rate of swaps paying token_0, can be changed.
+    /// * `token_1_creator_rate` - Creator fee rate of swaps paying token_1, can be changed.
+    /// * `create_pool_fee` - Fee in lamports charged for creating a pool, can be changed.
     ///
     pub fn create_amm_config(
         ctx: Context<CreateAmmConfig>,
         index: u64,
-        trade_fee_rate: u64,
-        protocol_fee_rate: u64,
-        fund_fee_rate: u64,
+        token_1_lp_rate: u64,
+        token_0_lp_rate: u64,
+        token_0_creator_rate: u64,
+        token_1_creator_rate: u64,
         create_pool_fee: u64,
     ) -> Result<()> {
         instructions::create_amm_config(
             ctx,
             index,
-            trade_fee_rate,
-            protocol_fee_rate,
-            fund_fee_rate,
+            token_1_lp_rate,
+            token_0_lp_rate,
+            token_0_creator_rate,
+            token_1_creator_rate,
             create_pool_fee,
         )
     }
 
-    /// Updates the owner of the amm config
-    /// Must be called by the current owner or admin
+    /// Updates the amm config, the change is queued when the config has an update delay,
+    /// configs have none until the owner sets `UpdateDelay`
+    /// Must be called by the config owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `update`- The field of the amm config to change and its new value
+    ///
+    pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, update: AmmConfigUpdate) -> Result<()> {
+        instructions::update_amm_config(ctx, update)
+    }
+
+    /// Apply the pending change of an amm config once its update delay has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn apply_pending_config(ctx: Context<ApplyPendingConfig>) -> Result<()> {
+        instructions::apply_pending_config(ctx)
+    }
+
+    /// Cancel the pending change of an amm config
+    /// Must be called by the config owner
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `trade_fee_rate`- The new trade fee rate of amm config, be set when `param` is 0
-    /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1
-    /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
-    /// * `new_owner`- The config's new owner, be set when `param` is 3
-    /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The vaule can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
     ///
-    pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
-        instructions::update_amm_config(ctx, param, value)
+    pub fn cancel_pending_config(ctx: Context<CancelPendingConfig>) -> Result<()> {
+        instructions::cancel_pending_config(ctx)
     }
 
     /// Update pool status for given vaule
@@ -86,12 +114,138 @@ pub mod raydium_cp_swap {
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `status` - The vaule of status
+    /// * `status` - The vaule of status, bit0 deposit, bit1 withdraw, bit2 swap,
+    ///   bit3 swap token_0 for token_1, bit4 swap token_1 for token_0
     ///
     pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Update the global status of an amm config, applied on top of every pool status of the config,
+    /// immediately regardless of the update delay
+    /// Must be called by the config owner or admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `status` - The vaule of global status, same bit layout as pool status
+    ///
+    pub fn update_global_status(ctx: Context<UpdateGlobalStatus>, status: u8) -> Result<()> {
+        instructions::update_global_status(ctx, status)
+    }
+
+    /// Propose a new owner of an amm config, the transfer completes when it accepts,
+    /// not subject to the update delay
+    /// Must be called by the config owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `new_owner` - The proposed owner, `Pubkey::default()` cancels a pending transfer
+    ///
+    pub fn transfer_amm_config_owner(
+        ctx: Context<TransferAmmConfigOwner>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_amm_config_owner(ctx, new_owner)
+    }
+
+    /// Accept the ownership of an amm config
+    /// Must be called by the pending owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn accept_amm_config_owner(ctx: Context<AcceptAmmConfigOwner>) -> Result<()> {
+        instructions::accept_amm_config_owner(ctx)
+    }
+
+    /// Set the merkle root of wallets allowed to swap before the pool `open_time`
+    /// Must be called by the pool creator before the pool is open, a new root invalidates the
+    /// allocations claimed under the previous one
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `merkle_root` - The root of allowlist leaves `keccak(wallet, allocation)`, zero to disable
+    /// * `early_access_open_time` - The timestamp allowed for allowlisted wallets to swap
+    ///
+    pub fn set_early_access(
+        ctx: Context<SetEarlyAccess>,
+        merkle_root: [u8; 32],
+        early_access_open_time: u64,
+    ) -> Result<()> {
+        instructions::set_early_access(ctx, merkle_root, early_access_open_time)
+    }
+
+    /// Cap the amount each wallet can buy for a duration after the pool `open_time`
+    /// Must be called by the pool creator before the pool is open
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `launch_buy_cap_duration` - The duration of the launch window after `open_time`, zero to disable
+    /// * `max_buy_amount_0` - Maximum amount of token_0 a wallet can buy, zero for no cap
+    /// * `max_buy_amount_1` - Maximum amount of token_1 a wallet can buy, zero for no cap
+    ///
+    pub fn set_launch_buy_cap(
+        ctx: Context<SetLaunchBuyCap>,
+        launch_buy_cap_duration: u64,
+        max_buy_amount_0: u64,
+        max_buy_amount_1: u64,
+    ) -> Result<()> {
+        instructions::set_launch_buy_cap(
+            ctx,
+            launch_buy_cap_duration,
+            max_buy_amount_0,
+            max_buy_amount_1,
+        )
+    }
+
+    /// Allow or deny a mint, creating its registry entry if needed. Allowed mints bypass the
+    /// extension checks on pool creation, denied mints can't be used to create pools and their
+    /// pools can't swap
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `mint` - The mint to add or change
+    /// * `kind` - Allow or deny
+    ///
+    pub fn set_mint_registry_entry(
+        ctx: Context<SetMintRegistryEntry>,
+        mint: Pubkey,
+        kind: MintListKind,
+    ) -> Result<()> {
+        instructions::set_mint_registry_entry(ctx, mint, kind)
+    }
+
+    /// Remove a mint from the registry, closing its entry
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `mint` - The mint to remove
+    ///
+    pub fn remove_mint_registry_entry(
+        ctx: Context<RemoveMintRegistryEntry>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_mint_registry_entry(ctx, mint)
+    }
+
+    /// Upgrade a pool state to the current layout version, reallocating it if needed
+    /// Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+        instructions::migrate_pool_state(ctx)
+    }
+
     /// Collect the protocol fee accrued to the pool
     ///
     /// # Arguments
@@ -100,8 +254,8 @@ pub mod raydium_cp_swap {
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
     /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
     ///
-    pub fn collect_protocol_fee(
-        ctx: Context<CollectProtocolFee>,
+    pub fn collect_protocol_fee<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectProtocolFee<'info>>,
         amount_0_requested: u64,
         amount_1_requested: u64,
     ) -> Result<()> {
@@ -116,8 +270,8 @@ pub mod raydium_cp_swap {
     /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
     /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
     ///
-    pub fn collect_fund_fee(
-        ctx: Context<CollectFundFee>,
+    pub fn collect_fund_fee<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectFundFee<'info>>,
         amount_0_requested: u64,
         amount_1_requested: u64,
     ) -> Result<()> {
@@ -133,8 +287,8 @@ pub mod raydium_cp_swap {
     /// * `init_amount_1` - the initial amount_1 to deposit
     /// * `open_time` - the timestamp allowed for swap
     ///
-    pub fn initialize(
-        ctx: Context<Initialize>,
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
         init_amount_0: u64,
         init_amount_1: u64,
         open_time: u64,
@@ -151,12 +305,12 @@ pub mod raydium_cp_swap {
     /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
     /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
     ///
-    pub fn deposit(
-        ctx: Context<Deposit>,
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
         lp_token_amount: u64,
         maximum_token_0_amount: u64,
         maximum_token_1_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<LpChangeReturnData> {
         instructions::deposit(
             ctx,
             lp_token_amount,
@@ -165,6 +319,45 @@ pub mod raydium_cp_swap {
         )
     }
 
+    /// Deposit token0 and token1 for the largest lp amount their maximum amounts allow
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, including transfer fees
+    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, including transfer fees
+    /// * `minimum_lp_out` - Minimum amount of lp token to receive, prevents excessive slippage
+    ///
+    pub fn deposit_by_amounts<'info>(
+        ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+        minimum_lp_out: u64,
+    ) -> Result<LpChangeReturnData> {
+        instructions::deposit_by_amounts(
+            ctx,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+            minimum_lp_out,
+        )
+    }
+
+    /// Deposit a single token, part of it is swapped so the rest is deposited at the pool ratio
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` - Amount of input token to deposit
+    /// * `minimum_lp_out` - Minimum amount of lp token to receive, prevents excessive slippage
+    ///
+    pub fn deposit_single_sided<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositSingleSided<'info>>,
+        amount_in: u64,
+        minimum_lp_out: u64,
+    ) -> Result<()> {
+        instructions::deposit_single_sided(ctx, amount_in, minimum_lp_out)
+    }
+
     /// Withdraw lp for token0 ande token1
     ///
     /// # Arguments
@@ -174,12 +367,12 @@ pub mod raydium_cp_swap {
     /// * `minimum_token_0_amount` -  Minimum amount of token 0 to receive, prevents excessive slippage
     /// * `minimum_token_1_amount` -  Minimum amount of token 1 to receive, prevents excessive slippage
     ///
-    pub fn withdraw(
-        ctx: Context<Withdraw>,
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
         lp_token_amount: u64,
         minimum_token_0_amount: u64,
         minimum_token_1_amount: u64,
-    ) -> Result<()> {
+    ) -> Result<LpChangeReturnData> {
         instructions::withdraw(
             ctx,
             lp_token_amount,
@@ -188,6 +381,99 @@ pub mod raydium_cp_swap {
         )
     }
 
+    /// Withdraw lp for a single token, the other token is swapped into it within the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn
+    /// * `minimum_amount_out` - Minimum amount of output token to receive, prevents excessive slippage
+    ///
+    pub fn withdraw_single_sided<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawSingleSided<'info>>,
+        lp_token_amount: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::withdraw_single_sided(ctx, lp_token_amount, minimum_amount_out)
+    }
+
+    /// Burn lp permanently, its liquidity stays in the pool and is recorded on the pool state
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_amount` - Amount of lp to burn
+    ///
+    pub fn burn_lp_permanently(ctx: Context<BurnLpPermanently>, lp_amount: u64) -> Result<()> {
+        instructions::burn_lp_permanently(ctx, lp_amount)
+    }
+
+    /// Lock lp in an escrow, released linearly between `start_time` and `end_time`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `index` - Index of the lock, an owner can hold several locks of a pool
+    /// * `lp_amount` - Amount of lp to lock
+    /// * `start_time` - Vesting starts, nothing can be unlocked before
+    /// * `end_time` - Vesting ends, everything can be unlocked after. Equal to `start_time` for a cliff lock
+    ///
+    pub fn lock_lp(
+        ctx: Context<LockLp>,
+        index: u64,
+        lp_amount: u64,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<()> {
+        instructions::lock_lp(ctx, index, lp_amount, start_time, end_time)
+    }
+
+    /// Unlock the vested lp of a lock, the lock and its escrow are closed once all of it is unlocked
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+        instructions::unlock_lp(ctx)
+    }
+
+    /// Claim the lp accrued by the fees of a lock, the locked liquidity stays in the escrow
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn claim_lp_lock_fee(ctx: Context<ClaimLpLockFee>) -> Result<()> {
+        instructions::claim_lp_lock_fee(ctx)
+    }
+
+    /// Claim the early access allocation of a wallet with a merkle proof, once per allowlist root
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `allocation` - Maximum amount of output token the wallet can receive before `open_time`
+    /// * `proof` - The merkle proof of the wallet leaf
+    ///
+    pub fn claim_early_access(
+        ctx: Context<ClaimEarlyAccess>,
+        allocation: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_early_access(ctx, allocation, proof)
+    }
+
+    /// Create the account tracking the buys of a wallet during the launch window
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_launch_buy_state(ctx: Context<CreateLaunchBuyState>) -> Result<()> {
+        instructions::create_launch_buy_state(ctx)
+    }
+
     /// Swap the tokens in the pool base input amount
     ///
     /// # Arguments
@@ -196,11 +482,11 @@ pub mod raydium_cp_swap {
     /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
     /// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
     ///
-    pub fn swap_base_input(
-        ctx: Context<Swap>,
+    pub fn swap_base_input<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
         amount_in: u64,
         minimum_amount_out: u64,
-    ) -> Result<()> {
+    ) -> Result<SwapReturnData> {
         instructions::swap_base_input(ctx, amount_in, minimum_amount_out)
     }
 
@@ -212,7 +498,96 @@ pub mod raydium_cp_swap {
     /// * `max_amount_in` -  input amount prevents excessive slippage
     /// * `amount_out` -  amount of output token
     ///
-    pub fn swap_base_output(ctx: Context<Swap>, max_amount_in: u64, amount_out: u64) -> Result<()> {
+    pub fn swap_base_output<'info>(
+        ctx: Context<'_, '_, '_, 'info, Swap<'info>>,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Result<SwapReturnData> {
         instructions::swap_base_output(ctx, max_amount_in, amount_out)
     }
+
+    /// Swap exact input across several pools, the output of a hop is the input of the next one
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, `remaining_accounts` holds the accounts of `Swap` for
+    ///   every hop followed by its transfer hook extra accounts, see `SWAP_ROUTE_HOP_ACCOUNTS_LEN`
+    /// * `amount_in` -  input amount of the first hop
+    /// * `minimum_amount_out` -  Minimum amount of output token of the final hop, prevents excessive slippage
+    /// * `hop_accounts_lens` -  Number of accounts of every hop in `remaining_accounts`
+    ///
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        hop_accounts_lens: Vec<u8>,
+    ) -> Result<()> {
+        instructions::swap_route(ctx, amount_in, minimum_amount_out, hop_accounts_lens)
+    }
+
+    /// Lend vault tokens to the receiver program, which must repay them plus the flash fee
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, `remaining_accounts` are passed to the receiver program
+    ///   and hold the transfer hook extra accounts of the borrowed mints
+    /// * `amount_0` -  amount of token_0 to borrow
+    /// * `amount_1` -  amount of token_1 to borrow
+    /// * `data` -  instruction data of the receiver program
+    ///
+    pub fn flash_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, FlashSwap<'info>>,
+        amount_0: u64,
+        amount_1: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash_swap(ctx, amount_0, amount_1, data)
+    }
+
+    /// Escrow input token of a pool until the pool price reaches the target price
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `index` - The index of the order, an owner can place several orders on a pool
+    /// * `amount_in` - The amount of input token to escrow
+    /// * `target_price_x32` - Minimum amount of output token per input token, as a Q32.32 fixed point number
+    /// * `keeper_bounty_rate` - The bounty paid to keepers from the filled input, denominated in hundredths of a bip (10^-6)
+    ///
+    pub fn place_limit_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceLimitOrder<'info>>,
+        index: u64,
+        amount_in: u64,
+        target_price_x32: u128,
+        keeper_bounty_rate: u64,
+    ) -> Result<()> {
+        instructions::place_limit_order(ctx, index, amount_in, target_price_x32, keeper_bounty_rate)
+    }
+
+    /// Cancel a limit order, return the remaining input token and close the order
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_limit_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelLimitOrder<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_limit_order(ctx)
+    }
+
+    /// Fill a limit order through the pool, permissionless, the keeper receives the bounty.
+    /// A fully filled order is closed with its escrow, the rent goes back to the owner
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` - The maximum amount of the remaining input token to fill
+    ///
+    pub fn fill_limit_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillLimitOrder<'info>>,
+        amount_in: u64,
+    ) -> Result<()> {
+        instructions::fill_limit_order(ctx, amount_in)
+    }
 }
@@ -1,6 +1,9 @@
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
+/// Protocol share of the trading fee of new configs, same as the former fixed share
+pub const DEFAULT_PROTOCOL_FEE_RATE: u64 = 200;
 
 /// Holds the current owner of the factory
 #[account]
@@ -18,16 +21,295 @@ pub struct AmmConfig {
     pub token_0_lp_rate: u64,
     /// The fund fee, denominated in hundredths of a bip (10^-6)
     pub token_0_creator_rate: u64,
-    /// Fee for create a new pool
+    /// The fund fee, denominated in hundredths of a bip (10^-6)
     pub token_1_creator_rate: u64,
-    /// Address of the protocol fee owner
+    /// Owner of the config, updates it and collects the protocol fee, may be a multisig PDA
     pub protocol_owner: Pubkey,
     /// Address of the fund fee owner
     pub fund_owner: Pubkey,
-    /// padding
-    pub padding: [u64; 16],
+    /// Bitwise status applied to every pool of this config, same layout as `PoolState::status`
+    pub global_status: u8,
+    /// Fee in lamports charged to the creator for creating a new pool
+    pub create_pool_fee: u64,
+    /// Bitwise policy of the optional Token-2022 extensions allowed for new pools,
+    /// see `MintExtensionPolicyBitIndex`
+    pub mint_extension_policy: u8,
+    pub padding_1: [u8; 7],
+    /// The flash swap fee on the borrowed amount, denominated in hundredths of a bip (10^-6)
+    pub flash_fee_rate: u64,
+    /// The referrer share of the trading fee, denominated in hundredths of a bip (10^-6)
+    pub referral_fee_rate: u64,
+    /// The owner proposed by `protocol_owner`, becomes the owner once it accepts
+    pub pending_protocol_owner: Pubkey,
+    /// Minimum delay in seconds between queuing a change of `update_amm_config` and applying it.
+    /// Opt-in: zero, the value of new and existing configs, applies changes immediately.
+    /// Owner transfers and global status updates are never delayed, so incidents can be handled
+    pub update_delay: u32,
+    /// `param` of the pending change
+    pub pending_param: u8,
+    pub padding_2: [u8; 3],
+    /// The timestamp the pending change can be applied from, zero when no change is pending
+    pub pending_effective_time: u64,
+    /// `value` of the pending change
+    pub pending_value: u64,
+    /// New fund owner of the pending change
+    pub pending_key: Pubkey,
+    /// The protocol share of the trading fee, denominated in hundredths of a bip (10^-6),
+    /// zero in configs created before it and read as `DEFAULT_PROTOCOL_FEE_RATE`
+    pub protocol_fee_rate: u64,
 }
 
 impl AmmConfig {
-    pub const LEN: usize = 8 + 1 + 8 + 2 + 4 * 8 + 32 * 2 + 8 * 16;
+    /// Same size as the first layout, its padding is used up so new fields need a realloc
+    pub const LEN: usize =
+        8 + 1 + 1 + 8 + 4 * 8 + 32 * 2 + 1 + 8 + 1 + 7 + 8 * 2 + 32 + 4 + 1 + 3 + 8 * 2 + 32 + 8;
+
+    /// Protocol part of the trading fee, rounded down to whole 10^-4 of `total_fee` like the
+    /// former fixed share so the default rate still charges `total_fee / 10000 * 2`
+    pub fn protocol_fee(&self, total_fee: u64) -> u64 {
+        let protocol_fee_rate = if self.protocol_fee_rate == 0 {
+            DEFAULT_PROTOCOL_FEE_RATE
+        } else {
+            self.protocol_fee_rate
+        };
+        u64::try_from(
+            u128::from(total_fee / 10000) * u128::from(protocol_fee_rate)
+                / u128::from(FEE_RATE_DENOMINATOR_VALUE / 10000),
+        )
+        .unwrap()
+    }
+
+    pub fn has_pending_change(&self) -> bool {
+        self.pending_effective_time != 0
+    }
+
+    pub fn clear_pending_change(&mut self) {
+        self.pending_param = 0;
+        self.pending_effective_time = 0;
+        self.pending_value = 0;
+        self.pending_key = Pubkey::default();
+    }
+
+    /// Queue `update` to be applied from `effective_time`
+    pub fn set_pending_change(&mut self, update: AmmConfigUpdate, effective_time: u64) {
+        let (param, value, key) = update.encode();
+        self.pending_param = param;
+        self.pending_value = value;
+        self.pending_key = key;
+        self.pending_effective_time = effective_time;
+    }
+
+    pub fn pending_change(&self) -> Option<AmmConfigUpdate> {
+        if !self.has_pending_change() {
+            return None;
+        }
+        AmmConfigUpdate::decode(self.pending_param, self.pending_value, self.pending_key)
+    }
+
+    /// The current value of the field changed by `update`
+    pub fn current(&self, update: AmmConfigUpdate) -> AmmConfigUpdate {
+        match update {
+            AmmConfigUpdate::FundOwner(_) => AmmConfigUpdate::FundOwner(self.fund_owner),
+            AmmConfigUpdate::Token1LpRate(_) => AmmConfigUpdate::Token1LpRate(self.token_1_lp_rate),
+            AmmConfigUpdate::Token0LpRate(_) => AmmConfigUpdate::Token0LpRate(self.token_0_lp_rate),
+            AmmConfigUpdate::Token0CreatorRate(_) => {
+                AmmConfigUpdate::Token0CreatorRate(self.token_0_creator_rate)
+            }
+            AmmConfigUpdate::Token1CreatorRate(_) => {
+                AmmConfigUpdate::Token1CreatorRate(self.token_1_creator_rate)
+            }
+            AmmConfigUpdate::DisableCreatePool(_) => {
+                AmmConfigUpdate::DisableCreatePool(self.disable_create_pool)
+            }
+            AmmConfigUpdate::CreatePoolFee(_) => {
+                AmmConfigUpdate::CreatePoolFee(self.create_pool_fee)
+            }
+            AmmConfigUpdate::MintExtensionPolicy(_) => {
+                AmmConfigUpdate::MintExtensionPolicy(self.mint_extension_policy)
+            }
+            AmmConfigUpdate::FlashFeeRate(_) => AmmConfigUpdate::FlashFeeRate(self.flash_fee_rate),
+            AmmConfigUpdate::ReferralFeeRate(_) => {
+                AmmConfigUpdate::ReferralFeeRate(self.referral_fee_rate)
+            }
+            AmmConfigUpdate::UpdateDelay(_) => AmmConfigUpdate::UpdateDelay(self.update_delay),
+            AmmConfigUpdate::ProtocolFeeRate(_) => {
+                AmmConfigUpdate::ProtocolFeeRate(self.protocol_fee_rate)
+            }
+        }
+    }
+
+    /// Apply `update`, rejecting invalid values
+    pub fn apply(&mut self, update: AmmConfigUpdate) -> Result<()> {
+        match update {
+            AmmConfigUpdate::FundOwner(fund_owner) => {
+                require_keys_neq!(fund_owner, Pubkey::default());
+                self.fund_owner = fund_owner
+            }
+            AmmConfigUpdate::Token1LpRate(rate) => self.token_1_lp_rate = rate,
+            AmmConfigUpdate::Token0LpRate(rate) => self.token_0_lp_rate = rate,
+            AmmConfigUpdate::Token0CreatorRate(rate) => self.token_0_creator_rate = rate,
+            AmmConfigUpdate::Token1CreatorRate(rate) => self.token_1_creator_rate = rate,
+            AmmConfigUpdate::DisableCreatePool(disable) => self.disable_create_pool = disable,
+            AmmConfigUpdate::CreatePoolFee(fee) => self.create_pool_fee = fee,
+            AmmConfigUpdate::MintExtensionPolicy(policy) => self.mint_extension_policy = policy,
+            AmmConfigUpdate::FlashFeeRate(rate) => {
+                require_gt!(FEE_RATE_DENOMINATOR_VALUE, rate);
+                self.flash_fee_rate = rate
+            }
+            AmmConfigUpdate::ReferralFeeRate(rate) => {
+                require_gte!(FEE_RATE_DENOMINATOR_VALUE, rate);
+                self.referral_fee_rate = rate
+            }
+            AmmConfigUpdate::UpdateDelay(delay) => self.update_delay = delay,
+            AmmConfigUpdate::ProtocolFeeRate(rate) => {
+                // zero is the rate of the configs created before `protocol_fee_rate`
+                require_gt!(rate, 0);
+                require_gte!(FEE_RATE_DENOMINATOR_VALUE, rate);
+                self.protocol_fee_rate = rate
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A change of one field of `AmmConfig` through `update_amm_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmConfigUpdate {
+    FundOwner(Pubkey),
+    Token1LpRate(u64),
+    Token0LpRate(u64),
+    Token0CreatorRate(u64),
+    Token1CreatorRate(u64),
+    DisableCreatePool(bool),
+    CreatePoolFee(u64),
+    MintExtensionPolicy(u8),
+    FlashFeeRate(u64),
+    ReferralFeeRate(u64),
+    UpdateDelay(u32),
+    ProtocolFeeRate(u64),
+}
+
+impl AmmConfigUpdate {
+    /// Fixed size encoding stored in the pending change of `AmmConfig`
+    pub fn encode(self) -> (u8, u64, Pubkey) {
+        match self {
+            AmmConfigUpdate::FundOwner(key) => (0, 0, key),
+            AmmConfigUpdate::Token1LpRate(value) => (1, value, Pubkey::default()),
+            AmmConfigUpdate::Token0LpRate(value) => (2, value, Pubkey::default()),
+            AmmConfigUpdate::Token0CreatorRate(value) => (3, value, Pubkey::default()),
+            AmmConfigUpdate::Token1CreatorRate(value) => (4, value, Pubkey::default()),
+            AmmConfigUpdate::DisableCreatePool(value) => (5, u64::from(value), Pubkey::default()),
+            AmmConfigUpdate::CreatePoolFee(value) => (6, value, Pubkey::default()),
+            AmmConfigUpdate::MintExtensionPolicy(value) => (7, u64::from(value), Pubkey::default()),
+            AmmConfigUpdate::FlashFeeRate(value) => (8, value, Pubkey::default()),
+            AmmConfigUpdate::ReferralFeeRate(value) => (9, value, Pubkey::default()),
+            AmmConfigUpdate::UpdateDelay(value) => (10, u64::from(value), Pubkey::default()),
+            AmmConfigUpdate::ProtocolFeeRate(value) => (11, value, Pubkey::default()),
+        }
+    }
+
+    pub fn decode(param: u8, value: u64, key: Pubkey) -> Option<Self> {
+        Some(match param {
+            0 => AmmConfigUpdate::FundOwner(key),
+            1 => AmmConfigUpdate::Token1LpRate(value),
+            2 => AmmConfigUpdate::Token0LpRate(value),
+            3 => AmmConfigUpdate::Token0CreatorRate(value),
+            4 => AmmConfigUpdate::Token1CreatorRate(value),
+            5 => AmmConfigUpdate::DisableCreatePool(value != 0),
+            6 => AmmConfigUpdate::CreatePoolFee(value),
+            7 => AmmConfigUpdate::MintExtensionPolicy(u8::try_from(value).ok()?),
+            8 => AmmConfigUpdate::FlashFeeRate(value),
+            9 => AmmConfigUpdate::ReferralFeeRate(value),
+            10 => AmmConfigUpdate::UpdateDelay(u32::try_from(value).ok()?),
+            11 => AmmConfigUpdate::ProtocolFeeRate(value),
+            _ => return None,
+        })
+    }
+}
+
+/// Bits of `AmmConfig::mint_extension_policy`, a set bit allows the extension
+pub enum MintExtensionPolicyBitIndex {
+    PermanentDelegate,
+    DefaultAccountState,
+}
+
+impl MintExtensionPolicyBitIndex {
+    pub fn is_allowed(self, mint_extension_policy: u8) -> bool {
+        mint_extension_policy & (1 << (self as u8)) != 0
+    }
+}
+
+#[cfg(test)]
+pub mod config_test {
+    use super::*;
+
+    #[test]
+    fn encode_decode_update() {
+        let updates = [
+            AmmConfigUpdate::FundOwner(Pubkey::new_unique()),
+            AmmConfigUpdate::Token1LpRate(1),
+            AmmConfigUpdate::Token0LpRate(2),
+            AmmConfigUpdate::Token0CreatorRate(3),
+            AmmConfigUpdate::Token1CreatorRate(4),
+            AmmConfigUpdate::DisableCreatePool(true),
+            AmmConfigUpdate::CreatePoolFee(6),
+            AmmConfigUpdate::MintExtensionPolicy(7),
+            AmmConfigUpdate::FlashFeeRate(8),
+            AmmConfigUpdate::ReferralFeeRate(9),
+            AmmConfigUpdate::UpdateDelay(10),
+            AmmConfigUpdate::ProtocolFeeRate(11),
+        ];
+        for update in updates {
+            let (param, value, key) = update.encode();
+            assert_eq!(AmmConfigUpdate::decode(param, value, key), Some(update));
+        }
+        assert_eq!(AmmConfigUpdate::decode(12, 0, Pubkey::default()), None);
+    }
+
+    #[test]
+    fn apply_update() {
+        let mut amm_config = AmmConfig::default();
+        let update = AmmConfigUpdate::ProtocolFeeRate(DEFAULT_PROTOCOL_FEE_RATE);
+        assert_eq!(
+            amm_config.current(update),
+            AmmConfigUpdate::ProtocolFeeRate(0)
+        );
+        amm_config.apply(update).unwrap();
+        assert_eq!(amm_config.current(update), update);
+
+        assert!(amm_config
+            .apply(AmmConfigUpdate::ProtocolFeeRate(0))
+            .is_err());
+        assert!(amm_config
+            .apply(AmmConfigUpdate::FundOwner(Pubkey::default()))
+            .is_err());
+        assert!(amm_config
+            .apply(AmmConfigUpdate::FlashFeeRate(FEE_RATE_DENOMINATOR_VALUE))
+            .is_err());
+    }
+
+    #[test]
+    fn protocol_fee_matches_fixed_share() {
+        let legacy_config = AmmConfig::default();
+        let default_config = AmmConfig {
+            protocol_fee_rate: DEFAULT_PROTOCOL_FEE_RATE,
+            ..Default::default()
+        };
+        for total_fee in [0, 1, 9999, 10000, 15000, 25000, 123_456_789, u64::MAX] {
+            let fixed_share = total_fee / 10000 * 2;
+            assert_eq!(legacy_config.protocol_fee(total_fee), fixed_share);
+            assert_eq!(default_config.protocol_fee(total_fee), fixed_share);
+        }
+
+        let half_rate_config = AmmConfig {
+            protocol_fee_rate: DEFAULT_PROTOCOL_FEE_RATE / 2,
+            ..Default::default()
+        };
+        assert_eq!(half_rate_config.protocol_fee(25000), 2);
+        let full_rate_config = AmmConfig {
+            protocol_fee_rate: FEE_RATE_DENOMINATOR_VALUE,
+            ..Default::default()
+        };
+        assert_eq!(full_rate_config.protocol_fee(25000), 20000);
+    }
 }
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+pub const EARLY_ACCESS_SEED: &str = "early_access";
+
+/// Allocation of an allowlisted wallet to swap before the pool `open_time`
+#[account]
+#[derive(Default, Debug)]
+pub struct EarlyAccessState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the allocation belongs to
+    pub pool_id: Pubkey,
+    /// The allowlisted wallet
+    pub owner: Pubkey,
+    /// Maximum amount of output token the wallet can receive before `open_time`
+    pub allocation: u64,
+    /// Amount of output token already received before `open_time`
+    pub amount_used: u64,
+    /// The allowlist root the allocation was claimed under, only valid while the pool keeps it
+    pub merkle_root: [u8; 32],
+}
+
+impl EarlyAccessState {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 * 2 + 32;
+
+    /// Consume allocation for a pre-open swap, return false if it exceeds the allocation
+    pub fn consume(&mut self, amount: u64) -> bool {
+        match self.amount_used.checked_add(amount) {
+            Some(amount_used) if amount_used <= self.allocation => {
+                self.amount_used = amount_used;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod early_access_test {
+    use super::*;
+
+    #[test]
+    fn consume_allocation() {
+        let mut early_access = EarlyAccessState {
+            allocation: 100,
+            ..Default::default()
+        };
+        assert_eq!(early_access.consume(60), true);
+        assert_eq!(early_access.amount_used, 60);
+        assert_eq!(early_access.consume(41), false);
+        assert_eq!(early_access.amount_used, 60);
+        assert_eq!(early_access.consume(40), true);
+        assert_eq!(early_access.amount_used, 100);
+        assert_eq!(early_access.consume(u64::MAX), false);
+    }
+}
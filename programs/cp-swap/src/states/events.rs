@@ -1,3 +1,4 @@
+use crate::states::AmmConfigUpdate;
 use anchor_lang::prelude::*;
 
 /// Emitted when deposit and withdraw
@@ -38,4 +39,146 @@ pub struct SwapEvent {
     pub input_transfer_fee: u64,
     pub output_transfer_fee: u64,
     pub base_input: bool,
+    /// share of the trading fee paid to the referrer, in input token
+    pub referral_fee: u64,
+}
+
+/// Emitted when lp is burned permanently
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LpBurnedEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub burner: Pubkey,
+    pub lp_amount: u64,
+    /// Total lp burned permanently of the pool, after this burn
+    pub lp_burned_amount: u64,
+    pub lp_supply: u64,
+}
+
+/// Emitted when flash swap
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FlashSwapEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub receiver_program: Pubkey,
+    /// amount of token_0 lent out of the vault
+    pub amount_0: u64,
+    /// amount of token_1 lent out of the vault
+    pub amount_1: u64,
+    /// flash fee of token_0 left to the liquidity providers
+    pub fee_0: u64,
+    /// flash fee of token_1 left to the liquidity providers
+    pub fee_1: u64,
+}
+
+/// Emitted when a limit order is filled, fully or partially
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LimitOrderFilledEvent {
+    #[index]
+    pub limit_order: Pubkey,
+    pub pool_id: Pubkey,
+    pub keeper: Pubkey,
+    /// input taken from the escrow, keeper bounty included
+    pub amount_in: u64,
+    /// output received by the owner, without transfer fee
+    pub amount_out: u64,
+    pub keeper_bounty: u64,
+    pub remaining_amount_in: u64,
+}
+
+/// Emitted when a change of `update_amm_config` is queued behind the config update delay
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigChangeQueuedEvent {
+    #[index]
+    pub amm_config: Pubkey,
+    pub update: AmmConfigUpdate,
+    /// The timestamp the change can be applied from
+    pub effective_time: u64,
+}
+
+/// Emitted when a pending config change is applied
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigChangeAppliedEvent {
+    #[index]
+    pub amm_config: Pubkey,
+    pub update: AmmConfigUpdate,
+}
+
+/// Emitted when a pending config change is cancelled by the config owner
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigChangeCancelledEvent {
+    #[index]
+    pub amm_config: Pubkey,
+    pub update: AmmConfigUpdate,
+}
+
+/// Emitted when a field of an amm config is changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AmmConfigUpdatedEvent {
+    #[index]
+    pub amm_config: Pubkey,
+    pub old_value: AmmConfigUpdate,
+    pub new_value: AmmConfigUpdate,
+}
+
+/// Emitted when a pool is created
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolCreatedEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub amm_config: Pubkey,
+    pub creator: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    /// amount transferred by the creator, before transfer fee
+    pub init_amount_0: u64,
+    /// amount transferred by the creator, before transfer fee
+    pub init_amount_1: u64,
+    pub lp_supply: u64,
+    pub open_time: u64,
+}
+
+/// Emitted when protocol fees are collected
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ProtocolFeeCollectedEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub recipient_token_0_account: Pubkey,
+    pub recipient_token_1_account: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when fund fees are collected
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FundFeeCollectedEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub recipient_token_0_account: Pubkey,
+    pub recipient_token_1_account: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when the status of a pool is changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolStatusChangedEvent {
+    #[index]
+    pub pool_id: Pubkey,
+    pub old_status: u8,
+    pub new_status: u8,
 }
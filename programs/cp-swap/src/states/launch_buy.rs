@@ -0,0 +1,65 @@
+use crate::curve::TradeDirection;
+use anchor_lang::prelude::*;
+
+pub const LAUNCH_BUY_SEED: &str = "launch_buy";
+
+/// Amounts bought by a wallet during the launch window of a pool
+#[account]
+#[derive(Default, Debug)]
+pub struct LaunchBuyState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool the buys belong to
+    pub pool_id: Pubkey,
+    /// The buyer wallet
+    pub owner: Pubkey,
+    /// Amount of token_0 bought during the launch window
+    pub bought_token_0: u64,
+    /// Amount of token_1 bought during the launch window
+    pub bought_token_1: u64,
+    /// padding
+    pub padding: [u64; 4],
+}
+
+impl LaunchBuyState {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 * 2 + 8 * 4;
+
+    /// Record a buy of output token, return false if it exceeds `max_buy_amount`
+    pub fn buy(
+        &mut self,
+        trade_direction: TradeDirection,
+        amount_out: u64,
+        max_buy_amount: u64,
+    ) -> bool {
+        let bought = match trade_direction {
+            TradeDirection::ZeroForOne => &mut self.bought_token_1,
+            TradeDirection::OneForZero => &mut self.bought_token_0,
+        };
+        match bought.checked_add(amount_out) {
+            Some(amount) if amount <= max_buy_amount => {
+                *bought = amount;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod launch_buy_test {
+    use super::*;
+
+    #[test]
+    fn buy_with_cap() {
+        let mut launch_buy = LaunchBuyState::default();
+        assert_eq!(launch_buy.buy(TradeDirection::ZeroForOne, 60, 100), true);
+        assert_eq!(launch_buy.bought_token_1, 60);
+        assert_eq!(launch_buy.buy(TradeDirection::ZeroForOne, 41, 100), false);
+        assert_eq!(launch_buy.bought_token_1, 60);
+        // the other token is tracked separately
+        assert_eq!(launch_buy.buy(TradeDirection::OneForZero, 50, 50), true);
+        assert_eq!(launch_buy.bought_token_0, 50);
+        assert_eq!(launch_buy.buy(TradeDirection::ZeroForOne, 40, 100), true);
+        assert_eq!(launch_buy.bought_token_1, 100);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use anchor_lang::prelude::*;
+
+pub const LIMIT_ORDER_SEED: &str = "limit_order";
+pub const LIMIT_ORDER_VAULT_SEED: &str = "limit_order_vault";
+
+/// Input token escrowed until the pool price of the input token reaches `target_price_x32`,
+/// then swapped through the pool by any keeper, in one or several fills.
+#[account]
+#[derive(Default, Debug)]
+pub struct LimitOrderState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// Index of the order, an owner can place several orders on a pool
+    pub index: u64,
+    /// The pool the order is filled against
+    pub pool_id: Pubkey,
+    /// The order owner, receives the output token and the cancelled input
+    pub owner: Pubkey,
+    /// The token account escrowing the input token
+    pub order_vault: Pubkey,
+    /// The mint sold by the order
+    pub input_mint: Pubkey,
+    /// The mint bought by the order
+    pub output_mint: Pubkey,
+    /// Minimum amount of output token per input token, as a Q32.32 fixed point number,
+    /// same unit as `PoolState::token_price_x32`
+    pub target_price_x32: u128,
+    /// The keeper bounty taken from the filled input, denominated in hundredths of a bip (10^-6)
+    pub keeper_bounty_rate: u64,
+    /// Input token escrowed when the order was placed
+    pub amount_in: u64,
+    /// Input token left in the escrow
+    pub remaining_amount_in: u64,
+    /// Output token received by the owner so far
+    pub filled_amount_out: u64,
+    /// padding
+    pub padding: [u64; 8],
+}
+
+impl LimitOrderState {
+    pub const LEN: usize = 8 + 1 + 8 + 32 * 5 + 16 + 8 * 4 + 8 * 8;
+
+    /// Keeper bounty taken from `amount_in` of a fill
+    pub fn keeper_bounty(&self, amount_in: u64) -> u64 {
+        u64::try_from(
+            u128::from(amount_in) * u128::from(self.keeper_bounty_rate)
+                / u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .unwrap()
+    }
+
+    /// Whether receiving `amount_out` for `amount_in` is at or above the target price
+    pub fn is_price_reached(&self, amount_in: u64, amount_out: u64) -> bool {
+        if amount_in == 0 {
+            return false;
+        }
+        let amount_out_x32 = u128::from(amount_out) << 32;
+        amount_out_x32 / u128::from(amount_in) >= self.target_price_x32
+    }
+
+    /// Record a fill of `amount_in` escrowed input, `amount_out` received by the owner
+    pub fn fill(&mut self, amount_in: u64, amount_out: u64) {
+        self.remaining_amount_in = self.remaining_amount_in.checked_sub(amount_in).unwrap();
+        self.filled_amount_out = self.filled_amount_out.checked_add(amount_out).unwrap();
+    }
+}
+
+#[cfg(test)]
+pub mod limit_order_test {
+    use super::*;
+    use crate::states::Q32;
+
+    #[test]
+    fn keeper_bounty() {
+        let limit_order = LimitOrderState {
+            keeper_bounty_rate: 1000,
+            ..Default::default()
+        };
+        assert_eq!(limit_order.keeper_bounty(1_000_000), 1000);
+        assert_eq!(limit_order.keeper_bounty(999), 0);
+        assert_eq!(limit_order.keeper_bounty(u64::MAX), u64::MAX / 1000);
+    }
+
+    #[test]
+    fn is_price_reached() {
+        // at least 2 output per input
+        let limit_order = LimitOrderState {
+            target_price_x32: 2 * Q32,
+            ..Default::default()
+        };
+        assert!(limit_order.is_price_reached(100, 200));
+        assert!(limit_order.is_price_reached(100, 201));
+        assert!(!limit_order.is_price_reached(100, 199));
+        assert!(!limit_order.is_price_reached(0, 200));
+
+        // at least half an output per input
+        let limit_order = LimitOrderState {
+            target_price_x32: Q32 / 2,
+            ..Default::default()
+        };
+        assert!(limit_order.is_price_reached(200, 100));
+        assert!(!limit_order.is_price_reached(201, 100));
+    }
+
+    #[test]
+    fn fill() {
+        let mut limit_order = LimitOrderState {
+            amount_in: 1000,
+            remaining_amount_in: 1000,
+            ..Default::default()
+        };
+        limit_order.fill(400, 800);
+        assert_eq!(limit_order.remaining_amount_in, 600);
+        assert_eq!(limit_order.filled_amount_out, 800);
+        limit_order.fill(600, 1100);
+        assert_eq!(limit_order.remaining_amount_in, 0);
+        assert_eq!(limit_order.filled_amount_out, 1900);
+    }
+}
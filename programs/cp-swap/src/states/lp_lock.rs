@@ -0,0 +1,181 @@
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+pub const LP_LOCK_SEED: &str = "lp_lock";
+pub const LP_LOCK_VAULT_SEED: &str = "lp_lock_vault";
+
+/// LP tokens escrowed until `end_time`, released linearly from `start_time`.
+/// Liquidity is measured as `lp_amount * sqrt(token_0 * token_1) / lp_supply`,
+/// so the fees accrued to the locked position can be claimed while the principal stays locked.
+#[account]
+#[derive(Default, Debug)]
+pub struct LpLockState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// Index of the lock, an owner can hold several locks of a pool
+    pub index: u64,
+    /// The pool the locked lp belongs to
+    pub pool_id: Pubkey,
+    /// The locker, receives the unlocked lp and the fees
+    pub owner: Pubkey,
+    /// The token account escrowing the locked lp
+    pub lp_vault: Pubkey,
+    /// Amount of lp in the escrow
+    pub lp_amount: u64,
+    /// Liquidity of the lp when locked
+    pub total_liquidity: u128,
+    /// Liquidity already unlocked
+    pub unlocked_liquidity: u128,
+    /// Vesting starts, nothing can be unlocked before
+    pub start_time: u64,
+    /// Vesting ends, everything can be unlocked after
+    pub end_time: u64,
+    /// padding
+    pub padding: [u64; 8],
+}
+
+impl LpLockState {
+    pub const LEN: usize = 8 + 1 + 8 + 32 * 3 + 8 + 16 * 2 + 8 * 2 + 8 * 8;
+
+    /// Liquidity of `lp_amount` in a pool with the given lp supply and vault amounts
+    pub fn liquidity(
+        lp_amount: u64,
+        lp_supply: u64,
+        token_0_amount: u64,
+        token_1_amount: u64,
+    ) -> Option<u128> {
+        if lp_supply == 0 {
+            return None;
+        }
+        let sqrt_k = U256::from(u128::from(token_0_amount) * u128::from(token_1_amount))
+            .integer_sqrt();
+        let liquidity = sqrt_k
+            .checked_mul(U256::from(lp_amount))?
+            .checked_div(U256::from(lp_supply))?;
+        Some(liquidity.as_u128())
+    }
+
+    /// Liquidity released by the vesting schedule at `block_timestamp`
+    pub fn vested_liquidity(&self, block_timestamp: u64) -> u128 {
+        if block_timestamp >= self.end_time {
+            self.total_liquidity
+        } else if block_timestamp <= self.start_time {
+            0
+        } else {
+            let elapsed = U256::from(block_timestamp - self.start_time);
+            let duration = U256::from(self.end_time - self.start_time);
+            (U256::from(self.total_liquidity) * elapsed / duration).as_u128()
+        }
+    }
+
+    /// Amount of lp and liquidity that can be unlocked at `block_timestamp`.
+    /// The lp is taken pro-rata from the escrow, so it includes its share of unclaimed fees.
+    pub fn unlockable(&self, block_timestamp: u64) -> (u64, u128) {
+        let locked_liquidity = self.total_liquidity - self.unlocked_liquidity;
+        let unlock_liquidity = self
+            .vested_liquidity(block_timestamp)
+            .saturating_sub(self.unlocked_liquidity);
+        if locked_liquidity == 0 || unlock_liquidity == 0 {
+            return (0, 0);
+        }
+        let lp_amount = U256::from(self.lp_amount) * U256::from(unlock_liquidity)
+            / U256::from(locked_liquidity);
+        (lp_amount.as_u64(), unlock_liquidity)
+    }
+
+    /// Amount of lp in the escrow above what is needed to back the locked liquidity
+    pub fn claimable_fee_lp_amount(
+        &self,
+        lp_supply: u64,
+        token_0_amount: u64,
+        token_1_amount: u64,
+    ) -> Option<u64> {
+        let locked_liquidity = self.total_liquidity - self.unlocked_liquidity;
+        let sqrt_k = U256::from(u128::from(token_0_amount) * u128::from(token_1_amount))
+            .integer_sqrt();
+        if sqrt_k.is_zero() {
+            return None;
+        }
+        // Round up the lp backing the locked liquidity, in favor of the lock
+        let (locked_lp_amount, remainder) = U256::from(locked_liquidity)
+            .checked_mul(U256::from(lp_supply))?
+            .div_mod(sqrt_k);
+        let locked_lp_amount = if remainder.is_zero() {
+            locked_lp_amount
+        } else {
+            locked_lp_amount + 1
+        };
+        if locked_lp_amount >= U256::from(self.lp_amount) {
+            return Some(0);
+        }
+        Some(self.lp_amount - locked_lp_amount.as_u64())
+    }
+}
+
+#[cfg(test)]
+pub mod lp_lock_test {
+    use super::*;
+
+    #[test]
+    fn vested_liquidity() {
+        let lp_lock = LpLockState {
+            total_liquidity: 1000,
+            start_time: 100,
+            end_time: 200,
+            ..Default::default()
+        };
+        assert_eq!(lp_lock.vested_liquidity(0), 0);
+        assert_eq!(lp_lock.vested_liquidity(100), 0);
+        assert_eq!(lp_lock.vested_liquidity(150), 500);
+        assert_eq!(lp_lock.vested_liquidity(200), 1000);
+        assert_eq!(lp_lock.vested_liquidity(u64::MAX), 1000);
+
+        // cliff lock
+        let lp_lock = LpLockState {
+            total_liquidity: 1000,
+            start_time: 200,
+            end_time: 200,
+            ..Default::default()
+        };
+        assert_eq!(lp_lock.vested_liquidity(199), 0);
+        assert_eq!(lp_lock.vested_liquidity(200), 1000);
+    }
+
+    #[test]
+    fn unlockable() {
+        let mut lp_lock = LpLockState {
+            lp_amount: 600,
+            total_liquidity: 1000,
+            start_time: 100,
+            end_time: 200,
+            ..Default::default()
+        };
+        assert_eq!(lp_lock.unlockable(100), (0, 0));
+        assert_eq!(lp_lock.unlockable(150), (300, 500));
+
+        lp_lock.lp_amount -= 300;
+        lp_lock.unlocked_liquidity += 500;
+        assert_eq!(lp_lock.unlockable(150), (0, 0));
+        assert_eq!(lp_lock.unlockable(175), (150, 250));
+        assert_eq!(lp_lock.unlockable(200), (300, 500));
+    }
+
+    #[test]
+    fn claimable_fee_lp_amount() {
+        // 100 of 1000 lp in a pool of 1000 * 1000 is 100 liquidity
+        assert_eq!(LpLockState::liquidity(100, 1000, 1000, 1000), Some(100));
+        assert_eq!(LpLockState::liquidity(100, 0, 1000, 1000), None);
+
+        let lp_lock = LpLockState {
+            lp_amount: 100,
+            total_liquidity: 100,
+            ..Default::default()
+        };
+        assert_eq!(lp_lock.claimable_fee_lp_amount(1000, 1000, 1000), Some(0));
+        // reserves grew 4x from fees, 25 lp still back the locked liquidity
+        assert_eq!(lp_lock.claimable_fee_lp_amount(1000, 4000, 4000), Some(75));
+        // reserves shrank, nothing to claim
+        assert_eq!(lp_lock.claimable_fee_lp_amount(1000, 900, 900), Some(0));
+        assert_eq!(lp_lock.claimable_fee_lp_amount(1000, 0, 900), None);
+    }
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+
+/// Seed of the registry entry of a mint, `[MINT_REGISTRY_SEED, mint]`
+pub const MINT_REGISTRY_SEED: &str = "mint_registry";
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum MintListKind {
+    /// Pools can be created for the mint regardless of its extensions
+    #[default]
+    Allow,
+    /// Pools can't be created for the mint and its pools can't swap
+    Deny,
+}
+
+/// A mint allowed or denied by the admin, one PDA per mint so swaps only load the entries
+/// of their own mints.
+///
+/// The mints of the former hardcoded whitelist are not migrated, the admin allows them with
+/// `set_mint_registry_entry` after upgrade:
+/// HVbpJAQGNpkgBaYBZQBR1t7yFdvaYVp2vCQQfKKEN4tM, Crn4x1Y2HUKko7ox2EZMT6N2t2ZyH7eKtwkBGVnhEq1g,
+/// FrBfWJ4qE5sCzKm3k3JaAtqZcXUh4LvJygDeketsrsH4, 2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo
+#[account]
+#[derive(Default, Debug)]
+pub struct MintRegistryEntry {
+    /// Bump to identify PDA
+    pub bump: u8,
+    pub mint: Pubkey,
+    pub kind: MintListKind,
+    /// padding
+    pub padding: [u64; 4],
+}
+
+impl MintRegistryEntry {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 8 * 4;
+
+    /// Load the kind of the entry, none if the account has not been created yet.
+    /// The address of `account_info` must be checked by the caller.
+    pub fn load(account_info: &AccountInfo) -> Result<Option<MintListKind>> {
+        if account_info.owner != &crate::id() || account_info.data_is_empty() {
+            return Ok(None);
+        }
+        let data = account_info.try_borrow_data()?;
+        Ok(Some(Self::try_deserialize(&mut &data[..])?.kind))
+    }
+
+    pub fn is_denied(account_info: &AccountInfo) -> Result<bool> {
+        Ok(Self::load(account_info)? == Some(MintListKind::Deny))
+    }
+}
+
+#[cfg(test)]
+pub mod mint_registry_test {
+    use super::*;
+
+    fn load_entry(owner: &Pubkey, data: &mut [u8]) -> Option<MintListKind> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let account_info =
+            AccountInfo::new(&key, false, false, &mut lamports, data, owner, false, 0);
+        MintRegistryEntry::load(&account_info).unwrap()
+    }
+
+    #[test]
+    fn load_entry_kind() {
+        // not created yet
+        assert_eq!(load_entry(&anchor_lang::system_program::ID, &mut []), None);
+
+        let mut entry = MintRegistryEntry {
+            mint: Pubkey::new_unique(),
+            kind: MintListKind::Deny,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        entry.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), MintRegistryEntry::LEN);
+        assert_eq!(
+            load_entry(&crate::id(), &mut data),
+            Some(MintListKind::Deny)
+        );
+        // only accounts of the program are entries
+        assert_eq!(load_entry(&Pubkey::new_unique(), &mut data), None);
+
+        entry.kind = MintListKind::Allow;
+        let mut data = Vec::new();
+        entry.try_serialize(&mut data).unwrap();
+        assert_eq!(
+            load_entry(&crate::id(), &mut data),
+            Some(MintListKind::Allow)
+        );
+    }
+}
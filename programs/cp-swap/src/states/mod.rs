@@ -7,3 +7,13 @@ pub mod events;
 pub use events::*;
 pub mod oracle;
 pub use oracle::*;
+pub mod early_access;
+pub use early_access::*;
+pub mod launch_buy;
+pub use launch_buy::*;
+pub mod lp_lock;
+pub use lp_lock::*;
+pub mod mint_registry;
+pub use mint_registry::*;
+pub mod limit_order;
+pub use limit_order::*;
@@ -2,18 +2,41 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
 use std::ops::{BitAnd, BitOr, BitXor};
 
-use crate::curve::AMM;
+use crate::curve::{
+    TradeDirection, AMM, DEFAULT_TOKEN_RESERVES, DEFAULT_VIRTUAL_SOL_RESERVE,
+    DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE, DEFUALT_VIRTUAL_TOKEN_RESERVE,
+};
+use crate::error::ErrorCode;
 /// Seed to derive account address and signature
 pub const POOL_SEED: &str = "pool";
 pub const POOL_LP_MINT_SEED: &str = "pool_lp_mint";
 pub const POOL_VAULT_SEED: &str = "pool_vault";
+/// Lp locked in the pool forever on initialize, never minted
+pub const LOCK_LP_AMOUNT: u64 = 100;
 
 pub const Q32: u128 = (u32::MAX as u128) + 1; // 2^32
 
+/// Current layout version of `PoolState`
+pub const POOL_STATE_VERSION: u8 = 2;
+/// Size of the accounts of version 0, without `amm`
+pub const POOL_STATE_V0_LEN: usize = PoolState::LEN - std::mem::size_of::<AMM>();
+
 pub enum PoolStatusBitIndex {
     Deposit,
     Withdraw,
     Swap,
+    SwapZeroForOne,
+    SwapOneForZero,
+}
+
+impl PoolStatusBitIndex {
+    /// The status bit that controls swaps in the given trade direction
+    pub fn swap_direction(trade_direction: TradeDirection) -> Self {
+        match trade_direction {
+            TradeDirection::ZeroForOne => PoolStatusBitIndex::SwapZeroForOne,
+            TradeDirection::OneForZero => PoolStatusBitIndex::SwapOneForZero,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq)]
@@ -22,6 +45,15 @@ pub enum PoolStatusBitFlag {
     Disable,
 }
 
+/// Layout versions, upgraded by `migrate_pool_state`:
+/// * 0 - the original layout, `recent_epoch` followed by `padding: [u64; 31]`,
+///   `POOL_STATE_V0_LEN` bytes
+/// * 1 - inserts `amm` after `recent_epoch`, the account grows by `size_of::<AMM>()`
+/// * 2 - adds `early_access_root`, `early_access_open_time`, `launch_buy_cap_duration`,
+///   `max_buy_amount_0`, `max_buy_amount_1`, `lp_burned_amount`, `lp_burner`, `version` and
+///   `padding_1`, all carved out of `padding` so they read zero in the accounts of 1
+///
+/// `version` itself reads zero in the accounts of 0 and 1, they are told apart by their size
 #[account(zero_copy(unsafe))]
 #[repr(packed)]
 #[derive(Default, Debug)]
@@ -56,6 +88,8 @@ pub struct PoolState {
     /// bit0, 1: disable deposit(vaule is 1), 0: normal
     /// bit1, 1: disable withdraw(vaule is 2), 0: normal
     /// bit2, 1: disable swap(vaule is 4), 0: normal
+    /// bit3, 1: disable swap token_0 for token_1(vaule is 8), 0: normal
+    /// bit4, 1: disable swap token_1 for token_0(vaule is 16), 0: normal
     pub status: u8,
 
     pub lp_mint_decimals: u8,
@@ -63,7 +97,7 @@ pub struct PoolState {
     pub mint_0_decimals: u8,
     pub mint_1_decimals: u8,
 
-    /// True circulating supply without burns and lock ups
+    /// Lp supply backed by the vaults, including the initial lock and lp burned permanently
     pub lp_supply: u64,
     /// The amounts of token_0 and token_1 that are owed to the liquidity provider.
     pub protocol_fees_token_0: u64,
@@ -78,8 +112,29 @@ pub struct PoolState {
     pub recent_epoch: u64,
     
     pub amm: AMM,
-    
-    pub padding: [u64; 31 ],
+
+    /// Merkle root of the wallets allowed to swap before `open_time`, zero when disabled
+    pub early_access_root: [u8; 32],
+    /// The timestamp allowed for early access swap in the pool.
+    pub early_access_open_time: u64,
+
+    /// Duration after `open_time` during which each wallet buy is capped, zero when disabled
+    pub launch_buy_cap_duration: u64,
+    /// Maximum amount of token_0 a single wallet can buy during the launch window, zero for no cap
+    pub max_buy_amount_0: u64,
+    /// Maximum amount of token_1 a single wallet can buy during the launch window, zero for no cap
+    pub max_buy_amount_1: u64,
+
+    /// Total lp burned permanently, still counted in `lp_supply` so its liquidity stays in the pool
+    pub lp_burned_amount: u64,
+    /// The last wallet that burned lp permanently
+    pub lp_burner: Pubkey,
+
+    /// Layout version of the account, see `POOL_STATE_VERSION`
+    pub version: u8,
+    pub padding_1: [u8; 7],
+
+    pub padding: [u64; 17],
 }
 
 impl PoolState {
@@ -120,7 +175,52 @@ impl PoolState {
         self.fund_fees_token_1 = 0;
         self.open_time = open_time;
         self.recent_epoch = Clock::get().unwrap().epoch;
-        self.padding = [0u64; 31];
+        self.early_access_root = [0u8; 32];
+        self.early_access_open_time = 0;
+        self.launch_buy_cap_duration = 0;
+        self.max_buy_amount_0 = 0;
+        self.max_buy_amount_1 = 0;
+        self.lp_burned_amount = 0;
+        self.lp_burner = Pubkey::default();
+        self.version = POOL_STATE_VERSION;
+        self.padding_1 = [0u8; 7];
+        self.padding = [0u64; 17];
+    }
+
+    /// Upgrade the account from `version` to `POOL_STATE_VERSION`, an account of version 0
+    /// must be reallocated to the current size first. Return false if it is already current
+    pub fn migrate(&mut self, version: u8) -> Result<bool> {
+        require_gte!(
+            POOL_STATE_VERSION,
+            version,
+            ErrorCode::UnknownPoolStateVersion
+        );
+        if version == POOL_STATE_VERSION {
+            return Ok(false);
+        }
+        for version in version..POOL_STATE_VERSION {
+            match version {
+                // `amm` takes the head of the original zeroed padding, the curve is set on
+                // the lp supply like `initialize` does
+                0 => {
+                    let mut amm = AMM::new(
+                        DEFAULT_VIRTUAL_SOL_RESERVE,
+                        DEFUALT_VIRTUAL_TOKEN_RESERVE,
+                        0,
+                        DEFAULT_TOKEN_RESERVES,
+                        DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE,
+                    );
+                    amm.apply_buy(self.lp_supply.into())
+                        .ok_or(ErrorCode::BuyResultNone)?;
+                    self.amm = amm;
+                }
+                // the fields added by 2 read zero from the padding
+                1 => {}
+                _ => return err!(ErrorCode::UnknownPoolStateVersion),
+            }
+        }
+        self.version = POOL_STATE_VERSION;
+        Ok(true)
     }
 
     pub fn set_status(&mut self, status: u8) {
@@ -139,8 +239,34 @@ impl PoolState {
 
     /// Get status by bit, if it is `noraml` status, return true
     pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+        self.get_status_by_bit_with_global(bit, 0)
+    }
+
+    /// Get status by bit with the amm config `global_status` OR-ed in,
+    /// if it is `noraml` status for both, return true
+    pub fn get_status_by_bit_with_global(&self, bit: PoolStatusBitIndex, global_status: u8) -> bool {
         let status = u8::from(1) << (bit as u8);
-        self.status.bitand(status) == 0
+        self.status.bitor(global_status).bitand(status) == 0
+    }
+
+    /// Whether allowlisted wallets can swap at the given timestamp before `open_time`
+    pub fn is_early_access_active(&self, block_timestamp: u64) -> bool {
+        self.early_access_root != [0u8; 32]
+            && block_timestamp >= self.early_access_open_time
+            && block_timestamp < self.open_time
+    }
+
+    /// Get the per wallet cap of output token for the given trade direction and timestamp,
+    /// return 0 if there is no cap
+    pub fn launch_max_buy_amount(&self, trade_direction: TradeDirection, block_timestamp: u64) -> u64 {
+        let launch_end_time = self.open_time.saturating_add(self.launch_buy_cap_duration);
+        if block_timestamp < self.open_time || block_timestamp >= launch_end_time {
+            return 0;
+        }
+        match trade_direction {
+            TradeDirection::ZeroForOne => self.max_buy_amount_1,
+            TradeDirection::OneForZero => self.max_buy_amount_0,
+        }
     }
 
     pub fn vault_amount_without_fee(&self, vault_0: u64, vault_1: u64) -> (u64, u64) {
@@ -247,5 +373,206 @@ pub mod pool_test {
                 false
             );
         }
+
+        #[test]
+        fn get_swap_direction_status_by_bit() {
+            let mut pool_state = PoolState::default();
+            pool_state.set_status(8); // 0001000, sell-only for token_1
+            assert_eq!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap), true);
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::swap_direction(
+                    TradeDirection::ZeroForOne
+                )),
+                false
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::swap_direction(
+                    TradeDirection::OneForZero
+                )),
+                true
+            );
+
+            pool_state.set_status_by_bit(
+                PoolStatusBitIndex::SwapZeroForOne,
+                PoolStatusBitFlag::Enable,
+            );
+            pool_state.set_status_by_bit(
+                PoolStatusBitIndex::SwapOneForZero,
+                PoolStatusBitFlag::Disable,
+            );
+            assert_eq!(pool_state.status, 16); // 0010000
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::SwapZeroForOne),
+                true
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::SwapOneForZero),
+                false
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit),
+                true
+            );
+        }
+
+        #[test]
+        fn early_access_window() {
+            let mut pool_state = PoolState::default();
+            pool_state.open_time = 200;
+            pool_state.early_access_open_time = 100;
+            // no merkle root, no early access
+            assert_eq!(pool_state.is_early_access_active(150), false);
+
+            pool_state.early_access_root = [1u8; 32];
+            assert_eq!(pool_state.is_early_access_active(99), false);
+            assert_eq!(pool_state.is_early_access_active(100), true);
+            assert_eq!(pool_state.is_early_access_active(199), true);
+            assert_eq!(pool_state.is_early_access_active(200), false);
+        }
+
+        #[test]
+        fn launch_max_buy_amount() {
+            let mut pool_state = PoolState::default();
+            pool_state.open_time = 100;
+            pool_state.max_buy_amount_0 = 10;
+            pool_state.max_buy_amount_1 = 20;
+            // no launch window
+            assert_eq!(
+                pool_state.launch_max_buy_amount(TradeDirection::ZeroForOne, 100),
+                0
+            );
+
+            pool_state.launch_buy_cap_duration = 50;
+            assert_eq!(
+                pool_state.launch_max_buy_amount(TradeDirection::ZeroForOne, 99),
+                0
+            );
+            assert_eq!(
+                pool_state.launch_max_buy_amount(TradeDirection::ZeroForOne, 100),
+                20
+            );
+            assert_eq!(
+                pool_state.launch_max_buy_amount(TradeDirection::OneForZero, 149),
+                10
+            );
+            assert_eq!(
+                pool_state.launch_max_buy_amount(TradeDirection::OneForZero, 150),
+                0
+            );
+        }
+
+        #[test]
+        fn get_status_by_bit_with_global() {
+            let mut pool_state = PoolState::default();
+            pool_state.set_status(1); // 0000001
+            let global_status = 4; // 0000100
+            assert_eq!(
+                pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Deposit, global_status),
+                false
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Withdraw, global_status),
+                true
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Swap, global_status),
+                false
+            );
+
+            // global status cleared, only the pool status applies
+            assert_eq!(
+                pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Swap, 0),
+                true
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Deposit, 0),
+                false
+            );
+
+            // pool status cleared, only the global status applies
+            pool_state.set_status(0);
+            assert_eq!(
+                pool_state.get_status_by_bit_with_global(PoolStatusBitIndex::Withdraw, 7),
+                false
+            );
+        }
+    }
+    mod pool_version_test {
+        use super::*;
+        use anchor_lang::Discriminator;
+
+        #[test]
+        fn migrate() {
+            let mut pool_state = PoolState::default();
+            assert_eq!({ pool_state.version }, 0);
+            assert!(pool_state.migrate(1).unwrap());
+            assert_eq!({ pool_state.version }, POOL_STATE_VERSION);
+            assert!(!pool_state.migrate(POOL_STATE_VERSION).unwrap());
+            assert_eq!({ pool_state.version }, POOL_STATE_VERSION);
+            // accounts of a newer program
+            assert!(pool_state.migrate(POOL_STATE_VERSION + 1).is_err());
+        }
+
+        #[test]
+        fn migrate_v0_buffer() {
+            let pool_state = PoolState {
+                amm_config: Pubkey::new_unique(),
+                lp_mint: Pubkey::new_unique(),
+                observation_key: Pubkey::new_unique(),
+                auth_bump: 254,
+                lp_supply: 1_000_000_000,
+                open_time: 100,
+                recent_epoch: 7,
+                ..Default::default()
+            };
+            // the original layout is the current one without `amm`, its padding zeroed
+            let amm_offset = std::mem::offset_of!(PoolState, amm);
+            let bytes = bytemuck::bytes_of(&pool_state);
+            let mut data = [
+                &PoolState::DISCRIMINATOR[..],
+                &bytes[..amm_offset],
+                &bytes[amm_offset + std::mem::size_of::<AMM>()..],
+            ]
+            .concat();
+            assert_eq!(data.len(), POOL_STATE_V0_LEN);
+
+            // reallocated, zero filled
+            data.resize(PoolState::LEN, 0);
+            let mut migrated: PoolState = bytemuck::pod_read_unaligned(&data[8..]);
+            assert_eq!({ migrated.version }, 0);
+            assert!(migrated.migrate(0).unwrap());
+            assert_eq!({ migrated.version }, POOL_STATE_VERSION);
+            assert_eq!(migrated.amm_config, pool_state.amm_config);
+            assert_eq!(migrated.lp_mint, pool_state.lp_mint);
+            assert_eq!(migrated.observation_key, pool_state.observation_key);
+            assert_eq!(migrated.auth_bump, pool_state.auth_bump);
+            assert_eq!({ migrated.lp_supply }, { pool_state.lp_supply });
+            assert_eq!({ migrated.open_time }, { pool_state.open_time });
+            assert_eq!({ migrated.recent_epoch }, { pool_state.recent_epoch });
+            assert_eq!({ migrated.early_access_root }, [0u8; 32]);
+            assert_eq!({ migrated.lp_burned_amount }, 0);
+
+            // the curve holds the lp supply like a pool initialized with it
+            let mut amm = AMM::new(
+                DEFAULT_VIRTUAL_SOL_RESERVE,
+                DEFUALT_VIRTUAL_TOKEN_RESERVE,
+                0,
+                DEFAULT_TOKEN_RESERVES,
+                DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE,
+            );
+            amm.apply_buy(pool_state.lp_supply.into()).unwrap();
+            assert_eq!({ migrated.amm.real_token_reserves }, amm.real_token_reserves);
+            assert_eq!({ migrated.amm.real_sol_reserves }, amm.real_sol_reserves);
+            assert_eq!(
+                { migrated.amm.virtual_token_reserves },
+                amm.virtual_token_reserves
+            );
+        }
+
+        #[test]
+        fn layout_size() {
+            // fields are carved out of the padding, existing accounts keep their size
+            assert_eq!(PoolState::LEN, 717);
+        }
     }
 }
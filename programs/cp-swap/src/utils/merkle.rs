@@ -0,0 +1,61 @@
+//! Merkle proof verification for allowlists
+//! Pairs are hashed in sorted order, so the proof does not carry the position of each node
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::keccak;
+
+/// The leaf of an allowlisted wallet with its allocation
+pub fn allocation_leaf(owner: &Pubkey, allocation: u64) -> [u8; 32] {
+    keccak::hashv(&[owner.as_ref(), &allocation.to_le_bytes()]).to_bytes()
+}
+
+/// Returns true if `leaf` is included in the tree of `root`
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed_hash = leaf;
+    for node in proof.iter() {
+        computed_hash = if computed_hash <= *node {
+            keccak::hashv(&[&computed_hash, node]).to_bytes()
+        } else {
+            keccak::hashv(&[node, &computed_hash]).to_bytes()
+        };
+    }
+    computed_hash == *root
+}
+
+#[cfg(test)]
+mod merkle_test {
+    use super::*;
+
+    fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).to_bytes()
+        } else {
+            keccak::hashv(&[&b, &a]).to_bytes()
+        }
+    }
+
+    #[test]
+    fn verify_allocation_proof() {
+        let owners: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = owners
+            .iter()
+            .enumerate()
+            .map(|(i, owner)| allocation_leaf(owner, 1000 * (i as u64 + 1)))
+            .collect();
+        let node_01 = hash_pair(leaves[0], leaves[1]);
+        let node_23 = hash_pair(leaves[2], leaves[3]);
+        let root = hash_pair(node_01, node_23);
+
+        assert!(verify_merkle_proof(&[leaves[1], node_23], &root, leaves[0]));
+        assert!(verify_merkle_proof(&[leaves[2], node_01], &root, leaves[3]));
+        // wrong allocation
+        assert!(!verify_merkle_proof(
+            &[leaves[1], node_23],
+            &root,
+            allocation_leaf(&owners[0], 2000)
+        ));
+        // wrong proof
+        assert!(!verify_merkle_proof(&[leaves[2], node_23], &root, leaves[0]));
+        // single leaf tree
+        assert!(verify_merkle_proof(&[], &leaves[0], leaves[0]));
+    }
+}
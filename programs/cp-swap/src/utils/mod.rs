@@ -1,7 +1,9 @@
 pub mod account_load;
 pub mod math;
+pub mod merkle;
 pub mod token;
 
 pub use account_load::*;
 pub use math::*;
+pub use merkle::*;
 pub use token::*;
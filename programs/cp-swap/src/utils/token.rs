@@ -1,4 +1,6 @@
 use crate::error::ErrorCode;
+use crate::states::{MintExtensionPolicyBitIndex, MintListKind};
+use crate::utils::U256;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     token::{Token, TokenAccount},
@@ -7,25 +9,20 @@ use anchor_spl::{
         spl_token_2022::{
             self,
             extension::{
+                interest_bearing_mint::InterestBearingConfig,
                 transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
                 ExtensionType, StateWithExtensions,
             },
         },
     },
+    token_2022_extensions::transfer_fee,
     token_interface::{
         initialize_account3, spl_token_2022::extension::BaseStateWithExtensions,
         InitializeAccount3, Mint,
     },
 };
-use std::collections::HashSet;
-
-const MINT_WHITELIST: [&'static str; 4] = [
-    "HVbpJAQGNpkgBaYBZQBR1t7yFdvaYVp2vCQQfKKEN4tM",
-    "Crn4x1Y2HUKko7ox2EZMT6N2t2ZyH7eKtwkBGVnhEq1g",
-    "FrBfWJ4qE5sCzKm3k3JaAtqZcXUh4LvJygDeketsrsH4",
-    "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo",
-];
 
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_from_user_to_pool_vault<'a>(
     authority: AccountInfo<'a>,
     from: AccountInfo<'a>,
@@ -34,10 +31,26 @@ pub fn transfer_from_user_to_pool_vault<'a>(
     token_program: AccountInfo<'a>,
     amount: u64,
     mint_decimals: u8,
+    additional_accounts: &[AccountInfo<'a>],
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
+    if *mint.owner == token_2022::Token2022::id() && !additional_accounts.is_empty() {
+        // resolves the transfer hook extra accounts from `additional_accounts`
+        return spl_token_2022::onchain::invoke_transfer_checked(
+            token_program.key,
+            from,
+            mint,
+            to_vault,
+            authority,
+            additional_accounts,
+            amount,
+            mint_decimals,
+            &[],
+        )
+        .map_err(Into::into);
+    }
     token_2022::transfer_checked(
         CpiContext::new(
             token_program.to_account_info(),
@@ -53,6 +66,7 @@ pub fn transfer_from_user_to_pool_vault<'a>(
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_from_pool_vault_to_user<'a>(
     authority: AccountInfo<'a>,
     from_vault: AccountInfo<'a>,
@@ -62,10 +76,26 @@ pub fn transfer_from_pool_vault_to_user<'a>(
     amount: u64,
     mint_decimals: u8,
     signer_seeds: &[&[&[u8]]],
+    additional_accounts: &[AccountInfo<'a>],
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
+    if *mint.owner == token_2022::Token2022::id() && !additional_accounts.is_empty() {
+        // resolves the transfer hook extra accounts from `additional_accounts`
+        return spl_token_2022::onchain::invoke_transfer_checked(
+            token_program.key,
+            from_vault,
+            mint,
+            to,
+            authority,
+            additional_accounts,
+            amount,
+            mint_decimals,
+            signer_seeds,
+        )
+        .map_err(Into::into);
+    }
     token_2022::transfer_checked(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
@@ -127,6 +157,215 @@ pub fn token_burn<'a>(
     )
 }
 
+/// Whether the mint is the wrapped SOL mint
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == anchor_spl::token::spl_token::native_mint::ID
+}
+
+/// Wrap lamports of `owner` into a WSOL token account
+pub fn wrap_sol<'a>(
+    owner: AccountInfo<'a>,
+    token_account: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program,
+            anchor_lang::system_program::Transfer {
+                from: owner,
+                to: token_account.clone(),
+            },
+        ),
+        amount,
+    )?;
+    token_2022::sync_native(CpiContext::new(
+        token_program,
+        token_2022::SyncNative {
+            account: token_account,
+        },
+    ))
+}
+
+/// Close a WSOL token account of `owner`, unwrapping all of its lamports back to `owner`
+pub fn unwrap_sol<'a>(
+    owner: AccountInfo<'a>,
+    token_account: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+) -> Result<()> {
+    token_2022::close_account(CpiContext::new(
+        token_program,
+        token_2022::CloseAccount {
+            account: token_account,
+            destination: owner.clone(),
+            authority: owner,
+        },
+    ))
+}
+
+/// Create the temporary WSOL account of `owner` holding `amount` of its wrapped lamports,
+/// used in place of a user token account omitted to pay or receive native SOL.
+/// It must be closed with `unwrap_sol` before the end of the instruction
+pub fn create_temp_wsol_account<'a>(
+    owner: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    temp_wsol_account: Option<&UncheckedAccount<'a>>,
+    temp_wsol_bump: Option<u8>,
+    system_program: Option<&Program<'a, System>>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+) -> Result<AccountInfo<'a>> {
+    if !is_native_mint(mint.key) {
+        return err!(ErrorCode::InvalidInput);
+    }
+    let (temp_wsol_account, temp_wsol_bump, system_program) =
+        match (temp_wsol_account, temp_wsol_bump, system_program) {
+            (Some(account), Some(bump), Some(system_program)) => (
+                account.to_account_info(),
+                bump,
+                system_program.to_account_info(),
+            ),
+            _ => return err!(ErrorCode::InvalidInput),
+        };
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        crate::TEMP_WSOL_SEED.as_bytes(),
+        owner.key.as_ref(),
+        &[temp_wsol_bump],
+    ]];
+    let space = TokenAccount::LEN;
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = temp_wsol_account.lamports();
+    if lamports == 0 {
+        anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::CreateAccount {
+                    from: owner.clone(),
+                    to: temp_wsol_account.clone(),
+                },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            token_program.key,
+        )?;
+    } else {
+        // lamports sent to the address beforehand would make create_account fail,
+        // they are kept and unwrapped to `owner` on close
+        let required_lamports = rent.saturating_sub(lamports);
+        if required_lamports > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: owner.clone(),
+                        to: temp_wsol_account.clone(),
+                    },
+                ),
+                required_lamports,
+            )?;
+        }
+        anchor_lang::system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::Allocate {
+                    account_to_allocate: temp_wsol_account.clone(),
+                },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        anchor_lang::system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::Assign {
+                    account_to_assign: temp_wsol_account.clone(),
+                },
+                signer_seeds,
+            ),
+            token_program.key,
+        )?;
+    }
+    initialize_account3(CpiContext::new(
+        token_program.clone(),
+        InitializeAccount3 {
+            account: temp_wsol_account.clone(),
+            mint: mint.clone(),
+            authority: owner.clone(),
+        },
+    ))?;
+    wrap_sol(
+        owner.clone(),
+        temp_wsol_account.clone(),
+        system_program,
+        token_program.clone(),
+        amount,
+    )?;
+    Ok(temp_wsol_account)
+}
+
+/// Close a token account owned by the program authority, the rent goes to `destination`
+pub fn close_token_account<'a>(
+    authority: AccountInfo<'a>,
+    token_account: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_2022::close_account(CpiContext::new_with_signer(
+        token_program,
+        token_2022::CloseAccount {
+            account: token_account,
+            destination,
+            authority,
+        },
+        signer_seeds,
+    ))
+}
+
+/// Close a token escrow owned by the program authority, the rent goes to `destination`.
+/// Transfer fees withheld in the escrow are harvested to the mint first, so it can close.
+pub fn close_escrow_account<'a>(
+    authority: AccountInfo<'a>,
+    token_account: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if *mint.owner != Token::id() {
+        let has_transfer_fee = {
+            let mint_data = mint.try_borrow_data()?;
+            let mint_state =
+                StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+            mint_state.get_extension::<TransferFeeConfig>().is_ok()
+        };
+        if has_transfer_fee {
+            transfer_fee::harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    token_program.clone(),
+                    transfer_fee::HarvestWithheldTokensToMint {
+                        token_program_id: token_program.clone(),
+                        mint,
+                    },
+                ),
+                vec![token_account.clone()],
+            )?;
+        }
+    }
+    close_token_account(
+        authority,
+        token_account,
+        destination,
+        token_program,
+        signer_seeds,
+    )
+}
+
 /// Calculate the fee for output amount
 pub fn get_transfer_inverse_fee(mint_info: &AccountInfo, post_fee_amount: u64) -> Result<u64> {
     if *mint_info.owner == Token::id() {
@@ -173,27 +412,135 @@ pub fn get_transfer_fee(mint_info: &AccountInfo, pre_fee_amount: u64) -> Result<
     Ok(fee)
 }
 
-pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
+pub fn is_supported_mint(
+    mint_account: &InterfaceAccount<Mint>,
+    mint_extension_policy: u8,
+    mint_list_kind: Option<MintListKind>,
+) -> Result<bool> {
+    if mint_list_kind == Some(MintListKind::Deny) {
+        return Ok(false);
+    }
     let mint_info = mint_account.to_account_info();
     if *mint_info.owner == Token::id() {
         return Ok(true);
     }
-    let mint_whitelist: HashSet<&str> = MINT_WHITELIST.into_iter().collect();
-    if mint_whitelist.contains(mint_account.key().to_string().as_str()) {
+    if mint_list_kind == Some(MintListKind::Allow) {
         return Ok(true);
     }
     let mint_data = mint_info.try_borrow_data()?;
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
     let extensions = mint.get_extension_types()?;
-    for e in extensions {
-        if e != ExtensionType::TransferFeeConfig
-            && e != ExtensionType::MetadataPointer
-            && e != ExtensionType::TokenMetadata
-        {
-            return Ok(false);
+    Ok(is_supported_extensions(&extensions, mint_extension_policy))
+}
+
+/// Whether a pool can be created for a mint with the given extensions,
+/// `mint_extension_policy` allows the extensions that are risky for liquidity providers
+pub fn is_supported_extensions(extensions: &[ExtensionType], mint_extension_policy: u8) -> bool {
+    extensions.iter().all(|e| match e {
+        ExtensionType::TransferFeeConfig
+        | ExtensionType::MetadataPointer
+        | ExtensionType::TokenMetadata
+        | ExtensionType::TransferHook
+        | ExtensionType::InterestBearingConfig
+        | ExtensionType::MintCloseAuthority => true,
+        ExtensionType::PermanentDelegate => {
+            MintExtensionPolicyBitIndex::PermanentDelegate.is_allowed(mint_extension_policy)
+        }
+        ExtensionType::DefaultAccountState => {
+            MintExtensionPolicyBitIndex::DefaultAccountState.is_allowed(mint_extension_policy)
         }
+        _ => false,
+    })
+}
+
+/// Amount with the interest accrued by an interest-bearing mint at `unix_timestamp`,
+/// used to price tokens by their UI amount. Saturates at `u64::MAX` instead of failing so
+/// an extreme rate can't block trades that only use it for the oracle
+pub fn get_interest_bearing_amount(
+    mint_info: &AccountInfo,
+    amount: u64,
+    unix_timestamp: i64,
+) -> Result<u64> {
+    if *mint_info.owner == Token::id() {
+        return Ok(amount);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let amount = if let Ok(interest_bearing_config) = mint.get_extension::<InterestBearingConfig>()
+    {
+        interest_bearing_amount(interest_bearing_config, amount, unix_timestamp).unwrap_or(u64::MAX)
+    } else {
+        amount
+    };
+    Ok(amount)
+}
+
+/// Seconds per year used by the interest-bearing extension
+const INTEREST_SECONDS_PER_YEAR: u128 = 31_556_736;
+const INTEREST_ONE_IN_BASIS_POINTS: u128 = 10_000;
+
+/// Same continuous compounding as the extension's `amount_to_ui_amount` with zero decimals,
+/// in integer math: the amount is exact and the interest scale is within 2^-55 relative,
+/// rounded down
+fn interest_bearing_amount(
+    interest_bearing_config: &InterestBearingConfig,
+    amount: u64,
+    unix_timestamp: i64,
+) -> Option<u64> {
+    let initialization_timestamp = i64::from(interest_bearing_config.initialization_timestamp);
+    let last_update_timestamp = i64::from(interest_bearing_config.last_update_timestamp);
+    let pre_update_timespan = last_update_timestamp.checked_sub(initialization_timestamp)?;
+    let post_update_timespan = unix_timestamp.checked_sub(last_update_timestamp)?;
+    // the exponent is the sum of both periods, e^a * e^b = e^(a + b)
+    let exponent_numerator = i128::from(i16::from(interest_bearing_config.pre_update_average_rate))
+        .checked_mul(i128::from(pre_update_timespan))?
+        .checked_add(
+            i128::from(i16::from(interest_bearing_config.current_rate))
+                .checked_mul(i128::from(post_update_timespan))?,
+        )?;
+    let exponent_denominator = INTEREST_SECONDS_PER_YEAR * INTEREST_ONE_IN_BASIS_POINTS;
+    if amount == 0 || exponent_numerator == 0 {
+        return Some(amount);
     }
-    Ok(true)
+
+    // e^64 already overflows any non zero amount, e^-64 rounds it to zero
+    if exponent_numerator.unsigned_abs() > 64 * exponent_denominator {
+        return if exponent_numerator > 0 {
+            None
+        } else {
+            Some(0)
+        };
+    }
+    let scale_x64 = exp_x64(exponent_numerator.unsigned_abs(), exponent_denominator)?;
+    let amount = if exponent_numerator > 0 {
+        (U256::from(amount) * scale_x64) >> 64
+    } else {
+        (U256::from(amount) << 64) / scale_x64
+    };
+    u64::try_from(amount).ok()
+}
+
+/// e^(numerator / denominator) as a Q64.64 number, for exponents up to 64
+fn exp_x64(numerator: u128, denominator: u128) -> Option<U256> {
+    let one_x64 = U256::one() << 64;
+    // halve the exponent to at most 1 so the series converges fast, then square back
+    let mut halvings = 0;
+    while numerator > denominator << halvings {
+        halvings += 1;
+    }
+    let denominator = U256::from(denominator) << halvings;
+    let mut term = one_x64;
+    let mut sum = one_x64;
+    let mut k = 1u64;
+    while !term.is_zero() {
+        term = term * U256::from(numerator) / denominator / U256::from(k);
+        sum += term;
+        k += 1;
+    }
+    for _ in 0..halvings {
+        sum = sum.checked_mul(sum)? >> 64;
+    }
+    Some(sum)
 }
 
 pub fn create_token_account<'a>(
@@ -242,3 +589,236 @@ pub fn create_token_account<'a>(
         },
     ))
 }
+
+#[cfg(test)]
+mod token_test {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        interest_bearing_mint::BasisPoints, mint_close_authority::MintCloseAuthority,
+        permanent_delegate::PermanentDelegate, BaseStateWithExtensionsMut, Extension,
+        StateWithExtensionsMut,
+    };
+    use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+
+    /// Data of an initialized Token-2022 mint with the default value of extension `E`
+    fn mint_data_with_extension<E: Extension + bytemuck::Pod + Default>() -> Vec<u8> {
+        let space = ExtensionType::try_calculate_account_len::<MintState>(&[E::TYPE]).unwrap();
+        let mut data = vec![0; space];
+        let mut mint =
+            StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        *mint.init_extension::<E>(true).unwrap() = E::default();
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn is_supported_mint_data(
+        data: &mut [u8],
+        mint_extension_policy: u8,
+        mint_list_kind: Option<MintListKind>,
+    ) -> bool {
+        let key = Pubkey::new_unique();
+        let owner = token_2022::ID;
+        let mut lamports = 0;
+        let account_info =
+            AccountInfo::new(&key, false, false, &mut lamports, data, &owner, false, 0);
+        let mint_account = InterfaceAccount::<Mint>::try_from(&account_info).unwrap();
+        is_supported_mint(&mint_account, mint_extension_policy, mint_list_kind).unwrap()
+    }
+
+    #[test]
+    fn supported_extensions() {
+        assert!(is_supported_extensions(&[], 0));
+        assert!(is_supported_extensions(
+            &[
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::TransferHook,
+                ExtensionType::InterestBearingConfig,
+                ExtensionType::MintCloseAuthority,
+            ],
+            0
+        ));
+        assert!(!is_supported_extensions(
+            &[ExtensionType::NonTransferable],
+            u8::MAX
+        ));
+        assert!(!is_supported_extensions(
+            &[ExtensionType::ConfidentialTransferMint],
+            u8::MAX
+        ));
+    }
+
+    #[test]
+    fn mint_close_authority() {
+        assert!(is_supported_extensions(
+            &[ExtensionType::MintCloseAuthority],
+            0
+        ));
+        let mut data = mint_data_with_extension::<MintCloseAuthority>();
+        assert!(is_supported_mint_data(&mut data, 0, None));
+    }
+
+    #[test]
+    fn registry_overrides_extension_checks() {
+        let mut data = mint_data_with_extension::<PermanentDelegate>();
+        assert!(!is_supported_mint_data(&mut data, 0, None));
+        assert!(is_supported_mint_data(
+            &mut data,
+            0,
+            Some(MintListKind::Allow)
+        ));
+        assert!(is_supported_mint_data(&mut data, 1 << 0, None));
+        assert!(!is_supported_mint_data(
+            &mut data,
+            1 << 0,
+            Some(MintListKind::Deny)
+        ));
+
+        let mut data = mint_data_with_extension::<MintCloseAuthority>();
+        assert!(!is_supported_mint_data(
+            &mut data,
+            0,
+            Some(MintListKind::Deny)
+        ));
+    }
+
+    #[test]
+    fn permanent_delegate_policy() {
+        let extensions = [
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::PermanentDelegate,
+        ];
+        assert!(!is_supported_extensions(&extensions, 0));
+        assert!(!is_supported_extensions(&extensions, 1 << 1));
+        assert!(is_supported_extensions(&extensions, 1 << 0));
+    }
+
+    #[test]
+    fn default_account_state_policy() {
+        let extensions = [ExtensionType::DefaultAccountState];
+        assert!(!is_supported_extensions(&extensions, 0));
+        assert!(!is_supported_extensions(&extensions, 1 << 0));
+        assert!(is_supported_extensions(&extensions, 1 << 1));
+        assert!(!is_supported_extensions(
+            &[
+                ExtensionType::DefaultAccountState,
+                ExtensionType::PermanentDelegate
+            ],
+            1 << 1
+        ));
+    }
+
+    #[test]
+    fn interest_bearing_amount_accrues() {
+        let seconds_per_year = 31_556_736;
+        let config = InterestBearingConfig {
+            initialization_timestamp: 0.into(),
+            last_update_timestamp: 0.into(),
+            pre_update_average_rate: BasisPoints::from(0),
+            current_rate: BasisPoints::from(500),
+            ..Default::default()
+        };
+        assert_eq!(
+            interest_bearing_amount(&config, 1_000_000, 0),
+            Some(1_000_000)
+        );
+        // e^0.05 after a year at 5%
+        assert_eq!(
+            interest_bearing_amount(&config, 1_000_000, seconds_per_year),
+            Some(1_051_271)
+        );
+
+        let config = InterestBearingConfig {
+            current_rate: BasisPoints::from(-500),
+            ..config
+        };
+        assert_eq!(
+            interest_bearing_amount(&config, 1_000_000, seconds_per_year),
+            Some(951_229)
+        );
+    }
+
+    #[test]
+    fn interest_bearing_amount_is_exact_for_large_amounts() {
+        let config = InterestBearingConfig {
+            initialization_timestamp: 0.into(),
+            last_update_timestamp: 100.into(),
+            pre_update_average_rate: BasisPoints::from(300),
+            current_rate: BasisPoints::from(500),
+            ..Default::default()
+        };
+        // no time elapsed since initialization keeps amounts above 2^53 exact
+        let no_interest_config = InterestBearingConfig {
+            last_update_timestamp: 0.into(),
+            ..config
+        };
+        let amount = (1u64 << 60) + 1;
+        assert_eq!(
+            interest_bearing_amount(&no_interest_config, amount, 0),
+            Some(amount)
+        );
+
+        // within the documented tolerance of the extension's floating point result
+        let seconds_per_year = 31_556_736;
+        for amount in [1u64, 999, 1_000_000, 1 << 53, u64::MAX / 4] {
+            let expected = config
+                .amount_to_ui_amount(amount, 0, seconds_per_year)
+                .unwrap()
+                .parse::<f64>()
+                .unwrap();
+            let actual = interest_bearing_amount(&config, amount, seconds_per_year).unwrap() as f64;
+            assert!((actual - expected).abs() <= 1.0 + expected * 1e-12);
+        }
+
+        // overflows beyond u64 and rounds to zero at very negative rates
+        let config = InterestBearingConfig {
+            current_rate: BasisPoints::from(i16::MAX),
+            ..config
+        };
+        assert_eq!(
+            interest_bearing_amount(&config, u64::MAX, seconds_per_year),
+            None
+        );
+        let config = InterestBearingConfig {
+            current_rate: BasisPoints::from(i16::MIN),
+            ..config
+        };
+        assert_eq!(
+            interest_bearing_amount(&config, 1_000_000, seconds_per_year * 10),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn interest_bearing_amount_saturates_on_overflow() {
+        let seconds_per_year = 31_556_736;
+        let mut data = mint_data_with_extension::<InterestBearingConfig>();
+        StateWithExtensionsMut::<MintState>::unpack(&mut data)
+            .unwrap()
+            .get_extension_mut::<InterestBearingConfig>()
+            .unwrap()
+            .current_rate = BasisPoints::from(i16::MAX);
+        let key = Pubkey::new_unique();
+        let owner = token_2022::ID;
+        let mut lamports = 0;
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        assert_eq!(
+            get_interest_bearing_amount(&account_info, u64::MAX, seconds_per_year).unwrap(),
+            u64::MAX
+        );
+        assert!(
+            get_interest_bearing_amount(&account_info, 1_000_000, seconds_per_year).unwrap()
+                > 1_000_000
+        );
+    }
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use common::*;
+use raydium_cp_swap::states::{AmmConfig, LpBurnedEvent};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use std::sync::Once;
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const LP_AMOUNT: u64 = 100_000_000;
+
+/// The syscall stubs of program-test only print `sol_log_data`, this logs it to the
+/// transaction with `sol_log`, as `Program log: Program data: ...`, and forwards the rest
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.0
+            .sol_log(&format!("Program data: {}", fields.join(" ")))
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+struct DefaultStubs;
+
+impl SyscallStubs for DefaultStubs {}
+
+/// Wrap the stubs program-test installs when it starts
+fn log_events() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let stubs = set_syscall_stubs(Box::new(DefaultStubs));
+        set_syscall_stubs(Box::new(LogDataStubs(stubs)));
+    });
+}
+
+/// Events of type `T` the program emitted in `log_messages`
+fn events<T: AnchorDeserialize + Discriminator>(log_messages: &[String]) -> Vec<T> {
+    log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program log: Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter(|data| data.starts_with(&T::DISCRIMINATOR))
+        .map(|data| T::deserialize(&mut &data[8..]).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn burn_lp_permanently_keeps_lp_supply() {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let keys = add_pool(
+        &mut program_test,
+        AmmConfig::default(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let owner = Keypair::new();
+    let owner_lp_token = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        owner_lp_token,
+        &keys.lp_mint,
+        owner.pubkey(),
+        LP_AMOUNT,
+    );
+    let mut context = program_test.start_with_context().await;
+    log_events();
+    let pool_before = pool_state(&mut context, keys.pool_id).await;
+
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::BurnLpPermanently {
+            owner: owner.pubkey(),
+            pool_state: keys.pool_id,
+            owner_lp_token,
+            lp_mint: keys.lp_mint.address,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::BurnLpPermanently {
+            lp_amount: LP_AMOUNT,
+        }
+        .data(),
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &owner],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    // the lp is burned from the owner and the mint
+    assert_eq!(token_amount(&mut context, owner_lp_token).await, 0);
+    let lp_mint = context
+        .banks_client
+        .get_account(keys.lp_mint.address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        spl_token::state::Mint::unpack(&lp_mint.data)
+            .unwrap()
+            .supply,
+        pool_before.lp_supply - LP_AMOUNT
+    );
+    // its liquidity stays in the pool, still counted in `lp_supply`
+    let pool_after = pool_state(&mut context, keys.pool_id).await;
+    assert_eq!({ pool_after.lp_supply }, { pool_before.lp_supply });
+    assert_eq!({ pool_after.lp_burned_amount }, LP_AMOUNT);
+    assert_eq!(pool_after.lp_burner, owner.pubkey());
+
+    let events = events::<LpBurnedEvent>(&result.metadata.unwrap().log_messages);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].pool_id, keys.pool_id);
+    assert_eq!(events[0].burner, owner.pubkey());
+    assert_eq!(events[0].lp_amount, LP_AMOUNT);
+    assert_eq!(events[0].lp_burned_amount, LP_AMOUNT);
+    assert_eq!(events[0].lp_supply, { pool_before.lp_supply });
+}
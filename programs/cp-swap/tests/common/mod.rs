@@ -0,0 +1,522 @@
+//! Program test fixture of a pool, its accounts are written directly instead of running
+//! `initialize` so the tests only depend on the instruction under test.
+
+// each test file compiles this module and uses only some of its helpers
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AccountSerialize, Discriminator, ToAccountMetas};
+use raydium_cp_swap::curve::{
+    AMM, DEFAULT_TOKEN_RESERVES, DEFAULT_VIRTUAL_SOL_RESERVE,
+    DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE, DEFUALT_VIRTUAL_TOKEN_RESERVE,
+};
+use raydium_cp_swap::states::{
+    AmmConfig, MintListKind, MintRegistryEntry, ObservationState, PoolState, MINT_REGISTRY_SEED,
+    OBSERVATION_SEED, POOL_STATE_VERSION, POOL_VAULT_SEED,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_token_2022::extension::transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig};
+use spl_token_2022::extension::transfer_hook::{TransferHook, TransferHookAccount};
+use spl_token_2022::extension::{
+    BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+};
+use spl_token_2022::state::{Account as Account2022, AccountState, Mint as Mint2022};
+
+/// Anchor entrypoints tie the accounts slice to the lifetime of the account infos,
+/// `processor!` can't express it so the slice is leaked for the test process
+fn process_cp_swap(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    raydium_cp_swap::entry(program_id, accounts, data)
+}
+
+fn process_flash_receiver(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    test_flash_receiver::entry(program_id, accounts, data)
+}
+
+fn process_transfer_hook(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    test_transfer_hook::entry(program_id, accounts, data)
+}
+
+/// The pool program and the test programs it invokes
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new(
+        "raydium_cp_swap",
+        raydium_cp_swap::id(),
+        processor!(process_cp_swap),
+    );
+    program_test.add_program(
+        "test_flash_receiver",
+        test_flash_receiver::id(),
+        processor!(process_flash_receiver),
+    );
+    program_test.add_program(
+        "test_transfer_hook",
+        test_transfer_hook::id(),
+        processor!(process_transfer_hook),
+    );
+    program_test
+}
+
+pub fn add_account(program_test: &mut ProgramTest, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// A mint of the pool and its token program
+#[derive(Clone, Copy)]
+pub struct TestMint {
+    pub address: Pubkey,
+    pub token_program: Pubkey,
+    pub decimals: u8,
+}
+
+pub fn add_mint(program_test: &mut ProgramTest, decimals: u8) -> TestMint {
+    add_mint_with_supply(program_test, decimals, None, 0)
+}
+
+fn add_mint_with_supply(
+    program_test: &mut ProgramTest,
+    decimals: u8,
+    mint_authority: Option<Pubkey>,
+    supply: u64,
+) -> TestMint {
+    let mut data = vec![0u8; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: mint_authority.into(),
+        supply,
+        decimals,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    let address = Pubkey::new_unique();
+    add_account(program_test, address, spl_token::id(), data);
+    TestMint {
+        address,
+        token_program: spl_token::id(),
+        decimals,
+    }
+}
+
+/// Add a Token-2022 mint invoking `hook_program_id` on every transfer
+pub fn add_transfer_hook_mint(
+    program_test: &mut ProgramTest,
+    decimals: u8,
+    hook_program_id: Pubkey,
+) -> TestMint {
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint2022>(&[ExtensionType::TransferHook])
+            .unwrap();
+    let mut data = vec![0u8; space];
+    let mut mint = StateWithExtensionsMut::<Mint2022>::unpack_uninitialized(&mut data).unwrap();
+    mint.init_extension::<TransferHook>(true)
+        .unwrap()
+        .program_id = Some(hook_program_id).try_into().unwrap();
+    mint.base.decimals = decimals;
+    mint.base.is_initialized = true;
+    mint.pack_base();
+    mint.init_account_type().unwrap();
+    let address = Pubkey::new_unique();
+    add_account(program_test, address, spl_token_2022::id(), data);
+    TestMint {
+        address,
+        token_program: spl_token_2022::id(),
+        decimals,
+    }
+}
+
+/// Add a Token-2022 mint charging `transfer_fee_basis_points` on every transfer
+pub fn add_transfer_fee_mint(
+    program_test: &mut ProgramTest,
+    decimals: u8,
+    transfer_fee_basis_points: u16,
+) -> TestMint {
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint2022>(&[ExtensionType::TransferFeeConfig])
+            .unwrap();
+    let mut data = vec![0u8; space];
+    let mut mint = StateWithExtensionsMut::<Mint2022>::unpack_uninitialized(&mut data).unwrap();
+    let transfer_fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: u64::MAX.into(),
+        transfer_fee_basis_points: transfer_fee_basis_points.into(),
+    };
+    let transfer_fee_config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+    transfer_fee_config.older_transfer_fee = transfer_fee;
+    transfer_fee_config.newer_transfer_fee = transfer_fee;
+    mint.base.decimals = decimals;
+    mint.base.is_initialized = true;
+    mint.pack_base();
+    mint.init_account_type().unwrap();
+    let address = Pubkey::new_unique();
+    add_account(program_test, address, spl_token_2022::id(), data);
+    TestMint {
+        address,
+        token_program: spl_token_2022::id(),
+        decimals,
+    }
+}
+
+pub fn add_token_account(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    mint: &TestMint,
+    owner: Pubkey,
+    amount: u64,
+) {
+    let base = Account2022 {
+        mint: mint.address,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..Default::default()
+    };
+    let data = if mint.token_program == spl_token_2022::id() {
+        // accounts of transfer hook mints are flagged while transferring,
+        // accounts of transfer fee mints withhold the fees they receive
+        let space = ExtensionType::try_calculate_account_len::<Account2022>(&[
+            ExtensionType::TransferHookAccount,
+            ExtensionType::TransferFeeAmount,
+        ])
+        .unwrap();
+        let mut data = vec![0u8; space];
+        let mut account =
+            StateWithExtensionsMut::<Account2022>::unpack_uninitialized(&mut data).unwrap();
+        account.init_extension::<TransferHookAccount>(true).unwrap();
+        account.init_extension::<TransferFeeAmount>(true).unwrap();
+        account.base = base;
+        account.pack_base();
+        account.init_account_type().unwrap();
+        data
+    } else {
+        let mut data = vec![0u8; Account2022::LEN];
+        base.pack_into_slice(&mut data);
+        data
+    };
+    add_account(program_test, address, mint.token_program, data);
+}
+
+/// The registry entry of `mint`, not created by the fixture so the mint is allowed
+pub fn mint_registry_entry(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MINT_REGISTRY_SEED.as_bytes(), mint.as_ref()],
+        &raydium_cp_swap::id(),
+    )
+    .0
+}
+
+/// Write the registry entry of `mint` with `kind`, like `set_mint_registry_entry` does
+pub fn add_mint_registry_entry(program_test: &mut ProgramTest, mint: &Pubkey, kind: MintListKind) {
+    let (address, bump) = Pubkey::find_program_address(
+        &[MINT_REGISTRY_SEED.as_bytes(), mint.as_ref()],
+        &raydium_cp_swap::id(),
+    );
+    let mut data = Vec::with_capacity(MintRegistryEntry::LEN);
+    MintRegistryEntry {
+        bump,
+        mint: *mint,
+        kind,
+        ..Default::default()
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    add_account(program_test, address, raydium_cp_swap::id(), data);
+}
+
+/// Addresses of a pool written by `add_pool`
+pub struct PoolKeys {
+    pub pool_id: Pubkey,
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub token_0_mint: TestMint,
+    pub token_1_mint: TestMint,
+    pub lp_mint: TestMint,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub observation_state: Pubkey,
+}
+
+/// Add a pool of `token_0_mint` and `token_1_mint` with `vault_0_amount` and `vault_1_amount`
+/// in its vaults, its lp supply and curve are set like `initialize` does
+pub fn add_pool(
+    program_test: &mut ProgramTest,
+    amm_config: AmmConfig,
+    token_0_mint: TestMint,
+    token_1_mint: TestMint,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+) -> PoolKeys {
+    add_pool_with(
+        program_test,
+        amm_config,
+        token_0_mint,
+        token_1_mint,
+        vault_0_amount,
+        vault_1_amount,
+        |_| {},
+    )
+}
+
+/// `add_pool` with the pool state changed by `update` before it is written
+pub fn add_pool_with(
+    program_test: &mut ProgramTest,
+    amm_config: AmmConfig,
+    token_0_mint: TestMint,
+    token_1_mint: TestMint,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+    update: impl FnOnce(&mut PoolState),
+) -> PoolKeys {
+    let program_id = raydium_cp_swap::id();
+    let pool_id = Pubkey::new_unique();
+    let (authority, auth_bump) =
+        Pubkey::find_program_address(&[raydium_cp_swap::AUTH_SEED.as_bytes()], &program_id);
+    let (token_0_vault, _) = Pubkey::find_program_address(
+        &[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_id.as_ref(),
+            token_0_mint.address.as_ref(),
+        ],
+        &program_id,
+    );
+    let (token_1_vault, _) = Pubkey::find_program_address(
+        &[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_id.as_ref(),
+            token_1_mint.address.as_ref(),
+        ],
+        &program_id,
+    );
+    let (observation_key, _) = Pubkey::find_program_address(
+        &[OBSERVATION_SEED.as_bytes(), pool_id.as_ref()],
+        &program_id,
+    );
+    let lp_supply = ((vault_0_amount as f64) * (vault_1_amount as f64)).sqrt() as u64;
+    let lp_mint = add_mint_with_supply(program_test, 9, Some(authority), lp_supply);
+    let keys = PoolKeys {
+        pool_id,
+        amm_config: Pubkey::new_unique(),
+        authority,
+        token_0_mint,
+        token_1_mint,
+        lp_mint,
+        token_0_vault,
+        token_1_vault,
+        observation_state: observation_key,
+    };
+
+    let mut data = Vec::with_capacity(AmmConfig::LEN);
+    amm_config.try_serialize(&mut data).unwrap();
+    data.resize(AmmConfig::LEN, 0);
+    add_account(program_test, keys.amm_config, program_id, data);
+
+    let mut amm = AMM::new(
+        DEFAULT_VIRTUAL_SOL_RESERVE,
+        DEFUALT_VIRTUAL_TOKEN_RESERVE,
+        0,
+        DEFAULT_TOKEN_RESERVES,
+        DEFUALT_INITIAL_VIRTUAL_TOKEN_RESERVE,
+    );
+    amm.apply_buy(lp_supply.into()).unwrap();
+    let mut pool_state = PoolState {
+        amm_config: keys.amm_config,
+        token_0_vault,
+        token_1_vault,
+        token_0_mint: token_0_mint.address,
+        token_1_mint: token_1_mint.address,
+        token_0_program: token_0_mint.token_program,
+        token_1_program: token_1_mint.token_program,
+        lp_mint: lp_mint.address,
+        observation_key,
+        auth_bump,
+        mint_0_decimals: token_0_mint.decimals,
+        mint_1_decimals: token_1_mint.decimals,
+        lp_mint_decimals: lp_mint.decimals,
+        lp_supply,
+        amm,
+        version: POOL_STATE_VERSION,
+        ..Default::default()
+    };
+    update(&mut pool_state);
+    let mut data = PoolState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&pool_state));
+    add_account(program_test, pool_id, program_id, data);
+
+    let observation_state = ObservationState {
+        pool_id,
+        ..Default::default()
+    };
+    let mut data = ObservationState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&observation_state));
+    add_account(program_test, observation_key, program_id, data);
+
+    add_token_account(
+        program_test,
+        token_0_vault,
+        &token_0_mint,
+        authority,
+        vault_0_amount,
+    );
+    add_token_account(
+        program_test,
+        token_1_vault,
+        &token_1_mint,
+        authority,
+        vault_1_amount,
+    );
+    keys
+}
+
+/// Accounts of a swap in the pool of `keys` without optional accounts, token_0 for token_1
+/// when `zero_for_one`
+pub fn swap_accounts(
+    keys: &PoolKeys,
+    payer: Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+    zero_for_one: bool,
+) -> raydium_cp_swap::accounts::Swap {
+    let (input_vault, output_vault, input_mint, output_mint) = if zero_for_one {
+        (
+            keys.token_0_vault,
+            keys.token_1_vault,
+            keys.token_0_mint,
+            keys.token_1_mint,
+        )
+    } else {
+        (
+            keys.token_1_vault,
+            keys.token_0_vault,
+            keys.token_1_mint,
+            keys.token_0_mint,
+        )
+    };
+    raydium_cp_swap::accounts::Swap {
+        payer,
+        authority: keys.authority,
+        amm_config: keys.amm_config,
+        pool_state: keys.pool_id,
+        input_token_account: Some(input_token_account),
+        output_token_account: Some(output_token_account),
+        input_vault,
+        output_vault,
+        input_token_program: input_mint.token_program,
+        output_token_program: output_mint.token_program,
+        input_token_mint: input_mint.address,
+        output_token_mint: output_mint.address,
+        observation_state: keys.observation_state,
+        input_mint_registry_entry: Some(mint_registry_entry(&input_mint.address)),
+        output_mint_registry_entry: Some(mint_registry_entry(&output_mint.address)),
+        early_access: None,
+        launch_buy_state: None,
+        system_program: None,
+        referrer_token_account: None,
+        temp_wsol_account: None,
+    }
+}
+
+/// Sign with the payer of the test context and `signers`, and process
+pub async fn process_instruction(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// Move to a new blockhash, so an instruction sent again isn't deduplicated with the first one
+pub async fn refresh_blockhash(context: &mut ProgramTestContext) {
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+}
+
+pub async fn token_amount(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<Account2022>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+/// Whether the error is the custom program error `code` of the first instruction
+pub fn is_program_error(err: BanksClientError, code: u32) -> bool {
+    err.unwrap() == TransactionError::InstructionError(0, InstructionError::Custom(code))
+}
+
+pub async fn pool_state(context: &mut ProgramTestContext, pool_id: Pubkey) -> PoolState {
+    let account = context
+        .banks_client
+        .get_account(pool_id)
+        .await
+        .unwrap()
+        .unwrap();
+    bytemuck::pod_read_unaligned(&account.data[8..8 + std::mem::size_of::<PoolState>()])
+}
+
+/// Accounts of a `deposit` or `deposit_by_amounts` of `owner`, paying from its token accounts
+pub fn deposit_accounts(
+    keys: &PoolKeys,
+    owner: Pubkey,
+    owner_lp_token: Pubkey,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+) -> Vec<AccountMeta> {
+    raydium_cp_swap::accounts::Deposit {
+        owner,
+        authority: keys.authority,
+        pool_state: keys.pool_id,
+        owner_lp_token,
+        token_0_account: Some(token_0_account),
+        token_1_account: Some(token_1_account),
+        token_0_vault: keys.token_0_vault,
+        token_1_vault: keys.token_1_vault,
+        token_program: spl_token::id(),
+        token_program_2022: spl_token_2022::id(),
+        vault_0_mint: keys.token_0_mint.address,
+        vault_1_mint: keys.token_1_mint.address,
+        lp_mint: keys.lp_mint.address,
+        amm_config: keys.amm_config,
+        system_program: None,
+        temp_wsol_account: None,
+    }
+    .to_account_metas(None)
+}
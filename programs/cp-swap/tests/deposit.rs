@@ -0,0 +1,285 @@
+mod common;
+
+use anchor_lang::{InstructionData, Result, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::curve::CurveCalculator;
+use raydium_cp_swap::instructions::deposit_cost;
+use raydium_cp_swap::states::{AmmConfig, Q32};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000_000;
+const MAXIMUM_TOKEN_0_AMOUNT: u64 = 1_000_000_000;
+const MAXIMUM_TOKEN_1_AMOUNT: u64 = 2_000_000_000;
+
+fn no_transfer_fee(_amount: u64) -> Result<u64> {
+    Ok(0)
+}
+
+#[tokio::test]
+async fn deposit_by_amounts_prices_with_the_curve() {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let keys = add_pool(
+        &mut program_test,
+        AmmConfig::default(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let owner = Keypair::new();
+    let owner_lp_token = Pubkey::new_unique();
+    let token_0_account = Pubkey::new_unique();
+    let token_1_account = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        owner_lp_token,
+        &keys.lp_mint,
+        owner.pubkey(),
+        0,
+    );
+    add_token_account(
+        &mut program_test,
+        token_0_account,
+        &token_0_mint,
+        owner.pubkey(),
+        MAXIMUM_TOKEN_0_AMOUNT,
+    );
+    add_token_account(
+        &mut program_test,
+        token_1_account,
+        &token_1_mint,
+        owner.pubkey(),
+        MAXIMUM_TOKEN_1_AMOUNT,
+    );
+    let mut context = program_test.start_with_context().await;
+    let pool_before = pool_state(&mut context, keys.pool_id).await;
+
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: deposit_accounts(
+            &keys,
+            owner.pubkey(),
+            owner_lp_token,
+            token_0_account,
+            token_1_account,
+        ),
+        data: raydium_cp_swap::instruction::DepositByAmounts {
+            maximum_token_0_amount: MAXIMUM_TOKEN_0_AMOUNT,
+            maximum_token_1_amount: MAXIMUM_TOKEN_1_AMOUNT,
+            minimum_lp_out: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+
+    let lp_amount = token_amount(&mut context, owner_lp_token).await;
+    let cost = deposit_cost(
+        &pool_before,
+        lp_amount,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+        no_transfer_fee,
+        no_transfer_fee,
+    )
+    .unwrap();
+    let mut amm = pool_before.amm;
+    let buy_result = amm.apply_buy(lp_amount.into()).unwrap();
+    assert_ne!(u128::from(buy_result.sol_amount), Q32);
+    assert!(cost.transfer_token_0_amount < cost.token_0_amount);
+
+    // the owner pays the curve price of the lp it got
+    assert_eq!(
+        token_amount(&mut context, token_0_account).await,
+        MAXIMUM_TOKEN_0_AMOUNT - cost.transfer_token_0_amount
+    );
+    assert_eq!(
+        token_amount(&mut context, token_1_account).await,
+        MAXIMUM_TOKEN_1_AMOUNT - cost.transfer_token_1_amount
+    );
+    let pool_after = pool_state(&mut context, keys.pool_id).await;
+    assert_eq!(
+        { pool_after.lp_supply },
+        { pool_before.lp_supply } + lp_amount
+    );
+    assert_eq!(
+        { pool_after.amm.real_token_reserves },
+        cost.amm.real_token_reserves
+    );
+
+    // one more lp exceeds the maximum amount of token_0, the binding one
+    let cost = deposit_cost(
+        &pool_before,
+        lp_amount + 1,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+        no_transfer_fee,
+        no_transfer_fee,
+    )
+    .unwrap();
+    assert!(cost.transfer_token_0_amount > MAXIMUM_TOKEN_0_AMOUNT);
+}
+
+const AMOUNT_IN: u64 = 2_000_000_000;
+
+#[tokio::test]
+async fn deposit_single_sided_matches_swap_then_deposit() {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let amm_config = AmmConfig {
+        token_0_lp_rate: 2500,
+        token_1_lp_rate: 2500,
+        ..Default::default()
+    };
+    // two identical pools, one deposited single sided and one swapped then deposited
+    let single_sided = add_pool(
+        &mut program_test,
+        amm_config.clone(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let swap_then_deposit = add_pool(
+        &mut program_test,
+        amm_config.clone(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let owner = Keypair::new();
+    let single_sided_lp_token = Pubkey::new_unique();
+    let swap_then_deposit_lp_token = Pubkey::new_unique();
+    let single_sided_token_0 = Pubkey::new_unique();
+    let token_0_account = Pubkey::new_unique();
+    let token_1_account = Pubkey::new_unique();
+    for (address, mint, amount) in [
+        (single_sided_lp_token, &single_sided.lp_mint, 0),
+        (swap_then_deposit_lp_token, &swap_then_deposit.lp_mint, 0),
+        (single_sided_token_0, &token_0_mint, AMOUNT_IN),
+        (token_0_account, &token_0_mint, AMOUNT_IN),
+        (token_1_account, &token_1_mint, 0),
+    ] {
+        add_token_account(&mut program_test, address, mint, owner.pubkey(), amount);
+    }
+    let mut context = program_test.start_with_context().await;
+
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::DepositSingleSided {
+            owner: owner.pubkey(),
+            authority: single_sided.authority,
+            amm_config: single_sided.amm_config,
+            pool_state: single_sided.pool_id,
+            owner_lp_token: single_sided_lp_token,
+            input_token_account: single_sided_token_0,
+            input_vault: single_sided.token_0_vault,
+            output_vault: single_sided.token_1_vault,
+            token_program: spl_token::id(),
+            input_token_program: spl_token::id(),
+            input_token_mint: token_0_mint.address,
+            lp_mint: single_sided.lp_mint.address,
+            observation_state: single_sided.observation_state,
+            output_token_mint: token_1_mint.address,
+            input_mint_registry_entry: mint_registry_entry(&token_0_mint.address),
+            output_mint_registry_entry: mint_registry_entry(&token_1_mint.address),
+            launch_buy_state: None,
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::DepositSingleSided {
+            amount_in: AMOUNT_IN,
+            minimum_lp_out: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+
+    let swap_amount = u64::try_from(
+        CurveCalculator::single_sided_swap_amount(
+            AMOUNT_IN.into(),
+            VAULT_AMOUNT.into(),
+            amm_config.token_0_creator_rate + amm_config.token_0_lp_rate,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::Swap {
+            payer: owner.pubkey(),
+            authority: swap_then_deposit.authority,
+            amm_config: swap_then_deposit.amm_config,
+            pool_state: swap_then_deposit.pool_id,
+            input_token_account: Some(token_0_account),
+            output_token_account: Some(token_1_account),
+            input_vault: swap_then_deposit.token_0_vault,
+            output_vault: swap_then_deposit.token_1_vault,
+            input_token_program: spl_token::id(),
+            output_token_program: spl_token::id(),
+            input_token_mint: token_0_mint.address,
+            output_token_mint: token_1_mint.address,
+            observation_state: swap_then_deposit.observation_state,
+            input_mint_registry_entry: Some(mint_registry_entry(&token_0_mint.address)),
+            output_mint_registry_entry: Some(mint_registry_entry(&token_1_mint.address)),
+            early_access: None,
+            launch_buy_state: None,
+            system_program: None,
+            referrer_token_account: None,
+            temp_wsol_account: None,
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::SwapBaseInput {
+            amount_in: swap_amount,
+            minimum_amount_out: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: deposit_accounts(
+            &swap_then_deposit,
+            owner.pubkey(),
+            swap_then_deposit_lp_token,
+            token_0_account,
+            token_1_account,
+        ),
+        data: raydium_cp_swap::instruction::DepositByAmounts {
+            maximum_token_0_amount: AMOUNT_IN - swap_amount,
+            maximum_token_1_amount: token_amount(&mut context, token_1_account).await,
+            minimum_lp_out: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+
+    let lp_amount = token_amount(&mut context, single_sided_lp_token).await;
+    assert!(lp_amount > 0);
+    assert_eq!(
+        lp_amount,
+        token_amount(&mut context, swap_then_deposit_lp_token).await
+    );
+    let single_sided_pool = pool_state(&mut context, single_sided.pool_id).await;
+    let swap_then_deposit_pool = pool_state(&mut context, swap_then_deposit.pool_id).await;
+    assert_eq!({ single_sided_pool.lp_supply }, {
+        swap_then_deposit_pool.lp_supply
+    });
+    assert_eq!({ single_sided_pool.amm.real_sol_reserves }, {
+        swap_then_deposit_pool.amm.real_sol_reserves
+    });
+}
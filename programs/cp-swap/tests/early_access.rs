@@ -0,0 +1,198 @@
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{AmmConfig, EARLY_ACCESS_SEED};
+use raydium_cp_swap::utils::allocation_leaf;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000_000;
+const ALLOCATION: u64 = 100_000_000;
+
+struct EarlyAccessTest {
+    context: ProgramTestContext,
+    keys: PoolKeys,
+    creator: Keypair,
+    user: Keypair,
+    early_access: Pubkey,
+    input_token_account: Pubkey,
+    output_token_account: Pubkey,
+}
+
+/// A pool not open yet whose allowlist is the single leaf of `user` with `ALLOCATION`
+async fn setup() -> EarlyAccessTest {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let creator = Keypair::new();
+    let user = Keypair::new();
+    let creator_key = creator.pubkey();
+    let user_root = allocation_leaf(&user.pubkey(), ALLOCATION);
+    let keys = add_pool_with(
+        &mut program_test,
+        AmmConfig::default(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+        |pool_state| {
+            pool_state.pool_creator = creator_key;
+            pool_state.open_time = i64::MAX as u64;
+            pool_state.early_access_root = user_root;
+        },
+    );
+    let (early_access, _) = Pubkey::find_program_address(
+        &[
+            EARLY_ACCESS_SEED.as_bytes(),
+            keys.pool_id.as_ref(),
+            user.pubkey().as_ref(),
+        ],
+        &raydium_cp_swap::id(),
+    );
+    let input_token_account = Pubkey::new_unique();
+    let output_token_account = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        input_token_account,
+        &token_0_mint,
+        user.pubkey(),
+        AMOUNT_IN * 2,
+    );
+    add_token_account(
+        &mut program_test,
+        output_token_account,
+        &token_1_mint,
+        user.pubkey(),
+        0,
+    );
+    // the user pays for its allocation account
+    program_test.add_account(
+        user.pubkey(),
+        solana_sdk::account::Account::new(1_000_000_000, 0, &system_program::id()),
+    );
+    EarlyAccessTest {
+        context: program_test.start_with_context().await,
+        keys,
+        creator,
+        user,
+        early_access,
+        input_token_account,
+        output_token_account,
+    }
+}
+
+fn claim_instruction(test: &EarlyAccessTest, allocation: u64) -> Instruction {
+    Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::ClaimEarlyAccess {
+            owner: test.user.pubkey(),
+            pool_state: test.keys.pool_id,
+            early_access: test.early_access,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        // a single leaf tree has an empty proof
+        data: raydium_cp_swap::instruction::ClaimEarlyAccess {
+            allocation,
+            proof: vec![],
+        }
+        .data(),
+    }
+}
+
+fn swap_instruction(test: &EarlyAccessTest) -> Instruction {
+    let mut accounts = swap_accounts(
+        &test.keys,
+        test.user.pubkey(),
+        test.input_token_account,
+        test.output_token_account,
+        true,
+    );
+    accounts.early_access = Some(test.early_access);
+    Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: accounts.to_account_metas(None),
+        data: raydium_cp_swap::instruction::SwapBaseInput {
+            amount_in: AMOUNT_IN,
+            minimum_amount_out: 1,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn new_allowlist_invalidates_claims() {
+    let mut test = setup().await;
+    let user = test.user.insecure_clone();
+    let instruction = claim_instruction(&test, ALLOCATION);
+    process_instruction(&mut test.context, instruction, &[&user])
+        .await
+        .unwrap();
+    // once per root, a new blockhash so the same transaction isn't deduplicated
+    refresh_blockhash(&mut test.context).await;
+    let instruction = claim_instruction(&test, ALLOCATION);
+    let err = process_instruction(&mut test.context, instruction, &[&user])
+        .await
+        .unwrap_err();
+    assert!(is_program_error(err, ErrorCode::NotApproved.into()));
+    let instruction = swap_instruction(&test);
+    process_instruction(&mut test.context, instruction, &[&user])
+        .await
+        .unwrap();
+
+    // the creator replaces the allowlist, the user allocation is halved
+    let new_allocation = ALLOCATION / 2;
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::SetEarlyAccess {
+            authority: test.creator.pubkey(),
+            pool_state: test.keys.pool_id,
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::SetEarlyAccess {
+            merkle_root: allocation_leaf(&user.pubkey(), new_allocation),
+            early_access_open_time: 0,
+        }
+        .data(),
+    };
+    let creator = test.creator.insecure_clone();
+    process_instruction(&mut test.context, instruction, &[&creator])
+        .await
+        .unwrap();
+
+    // the allocation claimed under the previous root is no longer valid
+    refresh_blockhash(&mut test.context).await;
+    let instruction = swap_instruction(&test);
+    let err = process_instruction(&mut test.context, instruction, &[&user])
+        .await
+        .unwrap_err();
+    assert!(is_program_error(err, ErrorCode::NotApproved.into()));
+    let instruction = claim_instruction(&test, ALLOCATION);
+    let err = process_instruction(&mut test.context, instruction, &[&user])
+        .await
+        .unwrap_err();
+    assert!(is_program_error(err, ErrorCode::InvalidMerkleProof.into()));
+
+    let instruction = claim_instruction(&test, new_allocation);
+    process_instruction(&mut test.context, instruction, &[&user])
+        .await
+        .unwrap();
+    refresh_blockhash(&mut test.context).await;
+    let instruction = swap_instruction(&test);
+    process_instruction(&mut test.context, instruction, &[&user])
+        .await
+        .unwrap();
+    let input_token_account = test.input_token_account;
+    assert_eq!(
+        token_amount(&mut test.context, input_token_account).await,
+        0
+    );
+}
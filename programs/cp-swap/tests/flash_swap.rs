@@ -0,0 +1,160 @@
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{AmmConfig, MintListKind};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const BORROW_AMOUNT: u64 = 10_000_000;
+/// 0.1% of `BORROW_AMOUNT`
+const FLASH_FEE: u64 = 10_000;
+
+struct FlashSwapTest {
+    context: ProgramTestContext,
+    keys: PoolKeys,
+    borrower: Keypair,
+    token_0_account: Pubkey,
+    token_1_account: Pubkey,
+}
+
+async fn setup() -> FlashSwapTest {
+    setup_with(|_, _| {}).await
+}
+
+/// `setup` with `update` adding accounts to the program test of the pool
+async fn setup_with(update: impl FnOnce(&mut ProgramTest, &PoolKeys)) -> FlashSwapTest {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let keys = add_pool(
+        &mut program_test,
+        AmmConfig {
+            flash_fee_rate: 1000,
+            ..Default::default()
+        },
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    // the borrower only holds enough to pay the fee
+    let borrower = Keypair::new();
+    let token_0_account = Pubkey::new_unique();
+    let token_1_account = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        token_0_account,
+        &token_0_mint,
+        borrower.pubkey(),
+        FLASH_FEE,
+    );
+    add_token_account(
+        &mut program_test,
+        token_1_account,
+        &token_1_mint,
+        borrower.pubkey(),
+        0,
+    );
+    update(&mut program_test, &keys);
+    FlashSwapTest {
+        context: program_test.start_with_context().await,
+        keys,
+        borrower,
+        token_0_account,
+        token_1_account,
+    }
+}
+
+/// Borrow `BORROW_AMOUNT` of token_0 and repay `repay_amount_0` through the test receiver
+fn flash_swap_instruction(test: &FlashSwapTest, repay_amount_0: u64) -> Instruction {
+    let keys = &test.keys;
+    let mut accounts = raydium_cp_swap::accounts::FlashSwap {
+        payer: test.borrower.pubkey(),
+        authority: keys.authority,
+        amm_config: keys.amm_config,
+        pool_state: keys.pool_id,
+        token_0_account: test.token_0_account,
+        token_1_account: test.token_1_account,
+        token_0_vault: keys.token_0_vault,
+        token_1_vault: keys.token_1_vault,
+        token_program: spl_token::id(),
+        token_program_2022: spl_token_2022::id(),
+        vault_0_mint: keys.token_0_mint.address,
+        vault_1_mint: keys.token_1_mint.address,
+        token_0_mint_registry_entry: mint_registry_entry(&keys.token_0_mint.address),
+        token_1_mint_registry_entry: mint_registry_entry(&keys.token_1_mint.address),
+        receiver_program: test_flash_receiver::id(),
+    }
+    .to_account_metas(None);
+    // the accounts of the receiver after the ones passed by flash_swap
+    accounts.extend([
+        AccountMeta::new_readonly(keys.token_0_mint.address, false),
+        AccountMeta::new_readonly(keys.token_1_mint.address, false),
+        AccountMeta::new_readonly(keys.token_0_mint.token_program, false),
+        AccountMeta::new_readonly(keys.token_1_mint.token_program, false),
+    ]);
+    Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts,
+        data: raydium_cp_swap::instruction::FlashSwap {
+            amount_0: BORROW_AMOUNT,
+            amount_1: 0,
+            data: test_flash_receiver::instruction::OnFlashSwap {
+                repay_amount_0,
+                repay_amount_1: 0,
+            }
+            .data(),
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn flash_swap_repaid_with_fee() {
+    let mut test = setup().await;
+    let instruction = flash_swap_instruction(&test, BORROW_AMOUNT + FLASH_FEE);
+    process_instruction(&mut test.context, instruction, &[&test.borrower])
+        .await
+        .unwrap();
+
+    let vault_0 = test.keys.token_0_vault;
+    let token_0_account = test.token_0_account;
+    assert_eq!(
+        token_amount(&mut test.context, vault_0).await,
+        VAULT_AMOUNT + FLASH_FEE
+    );
+    assert_eq!(token_amount(&mut test.context, token_0_account).await, 0);
+}
+
+#[tokio::test]
+async fn flash_swap_underpaid() {
+    let mut test = setup().await;
+    let instruction = flash_swap_instruction(&test, BORROW_AMOUNT + FLASH_FEE - 1);
+    let err = process_instruction(&mut test.context, instruction, &[&test.borrower])
+        .await
+        .unwrap_err();
+    assert!(is_program_error(err, ErrorCode::FlashSwapNotRepaid.into()));
+
+    let vault_0 = test.keys.token_0_vault;
+    assert_eq!(token_amount(&mut test.context, vault_0).await, VAULT_AMOUNT);
+}
+
+#[tokio::test]
+async fn flash_swap_of_a_denied_mint() {
+    let mut test = setup_with(|program_test, keys| {
+        add_mint_registry_entry(program_test, &keys.token_1_mint.address, MintListKind::Deny)
+    })
+    .await;
+    let instruction = flash_swap_instruction(&test, BORROW_AMOUNT + FLASH_FEE);
+    let err = process_instruction(&mut test.context, instruction, &[&test.borrower])
+        .await
+        .unwrap_err();
+    assert!(is_program_error(err, ErrorCode::MintDenied.into()));
+}
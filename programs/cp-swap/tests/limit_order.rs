@@ -0,0 +1,196 @@
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::states::{AmmConfig, LIMIT_ORDER_SEED, LIMIT_ORDER_VAULT_SEED};
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Mint as Mint2022;
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const ORDER_AMOUNT: u64 = 1_000_000;
+const KEEPER_BOUNTY_RATE: u64 = 1000;
+// half the pool price of the input token
+const TARGET_PRICE_X32: u128 = 1 << 31;
+
+/// Place an order selling the mint of `add_input_mint` in a pool, fill it fully, and check
+/// the order and its escrow are closed to the owner. Returns the input mint account.
+async fn fill_closes_the_order_and_its_escrow(
+    add_input_mint: fn(&mut ProgramTest) -> TestMint,
+) -> Account {
+    let mut program_test = program_test();
+    let input_mint = add_input_mint(&mut program_test);
+    let output_mint = add_mint(&mut program_test, 9);
+    let keys = add_pool(
+        &mut program_test,
+        AmmConfig::default(),
+        input_mint,
+        output_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let owner = Keypair::new();
+    let keeper = Keypair::new();
+    let owner_input_token = Pubkey::new_unique();
+    let owner_output_token = Pubkey::new_unique();
+    let keeper_input_token = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        owner_input_token,
+        &input_mint,
+        owner.pubkey(),
+        ORDER_AMOUNT,
+    );
+    add_token_account(
+        &mut program_test,
+        owner_output_token,
+        &output_mint,
+        owner.pubkey(),
+        0,
+    );
+    add_token_account(
+        &mut program_test,
+        keeper_input_token,
+        &input_mint,
+        keeper.pubkey(),
+        0,
+    );
+    program_test.add_account(
+        owner.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::id()),
+    );
+    let (limit_order, _) = Pubkey::find_program_address(
+        &[
+            LIMIT_ORDER_SEED.as_bytes(),
+            keys.pool_id.as_ref(),
+            owner.pubkey().as_ref(),
+            &0u64.to_be_bytes(),
+        ],
+        &raydium_cp_swap::id(),
+    );
+    let (order_vault, _) = Pubkey::find_program_address(
+        &[LIMIT_ORDER_VAULT_SEED.as_bytes(), limit_order.as_ref()],
+        &raydium_cp_swap::id(),
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::PlaceLimitOrder {
+            owner: owner.pubkey(),
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            limit_order,
+            order_vault,
+            input_token_account: owner_input_token,
+            input_token_mint: input_mint.address,
+            output_token_mint: output_mint.address,
+            input_token_program: input_mint.token_program,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::PlaceLimitOrder {
+            index: 0,
+            amount_in: ORDER_AMOUNT,
+            target_price_x32: TARGET_PRICE_X32,
+            keeper_bounty_rate: KEEPER_BOUNTY_RATE,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+
+    let owner_lamports = context
+        .banks_client
+        .get_balance(owner.pubkey())
+        .await
+        .unwrap();
+    let order_lamports = context.banks_client.get_balance(limit_order).await.unwrap()
+        + context.banks_client.get_balance(order_vault).await.unwrap();
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::FillLimitOrder {
+            keeper: keeper.pubkey(),
+            authority: keys.authority,
+            amm_config: keys.amm_config,
+            pool_state: keys.pool_id,
+            limit_order,
+            owner: owner.pubkey(),
+            order_vault,
+            owner_output_token_account: owner_output_token,
+            keeper_input_token_account: keeper_input_token,
+            input_vault: keys.token_0_vault,
+            output_vault: keys.token_1_vault,
+            input_token_program: input_mint.token_program,
+            output_token_program: output_mint.token_program,
+            input_token_mint: input_mint.address,
+            output_token_mint: output_mint.address,
+            observation_state: keys.observation_state,
+            input_mint_registry_entry: mint_registry_entry(&input_mint.address),
+            output_mint_registry_entry: mint_registry_entry(&output_mint.address),
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::FillLimitOrder {
+            amount_in: u64::MAX,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&keeper])
+        .await
+        .unwrap();
+
+    assert!(token_amount(&mut context, owner_output_token).await > 0);
+    assert!(token_amount(&mut context, keeper_input_token).await > 0);
+    // both order accounts are closed to the owner
+    for address in [limit_order, order_vault] {
+        assert!(context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(owner.pubkey())
+            .await
+            .unwrap(),
+        owner_lamports + order_lamports
+    );
+    context
+        .banks_client
+        .get_account(input_mint.address)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn fill_closes_a_token_order() {
+    fill_closes_the_order_and_its_escrow(|program_test| add_mint(program_test, 6)).await;
+}
+
+#[tokio::test]
+async fn fill_closes_a_token_2022_order_holding_transfer_fees() {
+    let input_mint = fill_closes_the_order_and_its_escrow(|program_test| {
+        add_transfer_fee_mint(program_test, 6, 100)
+    })
+    .await;
+    // the fees withheld in the escrow were harvested to the mint
+    let input_mint = StateWithExtensions::<Mint2022>::unpack(&input_mint.data).unwrap();
+    let withheld_amount = input_mint
+        .get_extension::<TransferFeeConfig>()
+        .unwrap()
+        .withheld_amount;
+    assert!(u64::from(withheld_amount) > 0);
+}
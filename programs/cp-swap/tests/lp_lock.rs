@@ -0,0 +1,139 @@
+mod common;
+
+use anchor_lang::{AccountSerialize, InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::states::{AmmConfig, LpLockState};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const LP_AMOUNT: u64 = 100_000_000;
+const LOCK_DURATION: u64 = 1000;
+
+async fn set_timestamp(context: &mut ProgramTestContext, unix_timestamp: u64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp as i64;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn unlock_lp_closes_the_lock_once_fully_unlocked() {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let keys = add_pool(
+        &mut program_test,
+        AmmConfig::default(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let owner = Keypair::new();
+    let owner_lp_token = Pubkey::new_unique();
+    let lp_lock = Pubkey::new_unique();
+    let lp_vault = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        owner_lp_token,
+        &keys.lp_mint,
+        owner.pubkey(),
+        0,
+    );
+    add_token_account(
+        &mut program_test,
+        lp_vault,
+        &keys.lp_mint,
+        keys.authority,
+        LP_AMOUNT,
+    );
+    program_test.add_account(
+        owner.pubkey(),
+        Account::new(1_000_000_000, 0, &system_program::id()),
+    );
+    // a lock vesting from the start of the test for `LOCK_DURATION`
+    let start_time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let mut data = Vec::with_capacity(LpLockState::LEN);
+    LpLockState {
+        pool_id: keys.pool_id,
+        owner: owner.pubkey(),
+        lp_vault,
+        lp_amount: LP_AMOUNT,
+        total_liquidity: LP_AMOUNT.into(),
+        start_time,
+        end_time: start_time + LOCK_DURATION,
+        ..Default::default()
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    data.resize(LpLockState::LEN, 0);
+    add_account(&mut program_test, lp_lock, raydium_cp_swap::id(), data);
+    let mut context = program_test.start_with_context().await;
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::UnlockLp {
+            owner: owner.pubkey(),
+            authority: keys.authority,
+            pool_state: keys.pool_id,
+            lp_lock,
+            lp_vault,
+            owner_lp_token,
+            lp_mint: keys.lp_mint.address,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::UnlockLp {}.data(),
+    };
+
+    // half way through the vesting, the lock stays open
+    set_timestamp(&mut context, start_time + LOCK_DURATION / 2).await;
+    process_instruction(&mut context, instruction.clone(), &[&owner])
+        .await
+        .unwrap();
+    assert_eq!(
+        token_amount(&mut context, owner_lp_token).await,
+        LP_AMOUNT / 2
+    );
+    assert_eq!(token_amount(&mut context, lp_vault).await, LP_AMOUNT / 2);
+
+    let owner_lamports = context
+        .banks_client
+        .get_balance(owner.pubkey())
+        .await
+        .unwrap();
+    let lock_lamports = context.banks_client.get_balance(lp_lock).await.unwrap()
+        + context.banks_client.get_balance(lp_vault).await.unwrap();
+    set_timestamp(&mut context, start_time + LOCK_DURATION).await;
+    refresh_blockhash(&mut context).await;
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+    assert_eq!(token_amount(&mut context, owner_lp_token).await, LP_AMOUNT);
+    // both lock accounts are closed to the owner
+    for address in [lp_lock, lp_vault] {
+        assert!(context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(owner.pubkey())
+            .await
+            .unwrap(),
+        owner_lamports + lock_lamports
+    );
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::{AmmConfig, MintListKind};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000_000;
+/// Accounts of `Swap` before the mint registry, up to `observation_state`
+const LEGACY_SWAP_ACCOUNTS_LEN: usize = 13;
+
+#[tokio::test]
+async fn swap_checks_the_registry_entries_it_is_given() {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    add_mint_registry_entry(&mut program_test, &token_1_mint.address, MintListKind::Deny);
+    let keys = add_pool(
+        &mut program_test,
+        AmmConfig::default(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let user = Keypair::new();
+    let input_token_account = Pubkey::new_unique();
+    let output_token_account = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        input_token_account,
+        &token_0_mint,
+        user.pubkey(),
+        AMOUNT_IN,
+    );
+    add_token_account(
+        &mut program_test,
+        output_token_account,
+        &token_1_mint,
+        user.pubkey(),
+        0,
+    );
+    let mut context = program_test.start_with_context().await;
+    let accounts = swap_accounts(
+        &keys,
+        user.pubkey(),
+        input_token_account,
+        output_token_account,
+        true,
+    )
+    .to_account_metas(None);
+    let swap_instruction = |accounts| Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts,
+        data: raydium_cp_swap::instruction::SwapBaseInput {
+            amount_in: AMOUNT_IN,
+            minimum_amount_out: 1,
+        }
+        .data(),
+    };
+
+    let err = process_instruction(&mut context, swap_instruction(accounts.clone()), &[&user])
+        .await
+        .unwrap_err();
+    assert!(is_program_error(err, ErrorCode::MintDenied.into()));
+
+    // clients built before the registry don't pass its entries and keep swapping
+    let legacy_accounts = accounts[..LEGACY_SWAP_ACCOUNTS_LEN].to_vec();
+    process_instruction(&mut context, swap_instruction(legacy_accounts), &[&user])
+        .await
+        .unwrap();
+    assert_eq!(token_amount(&mut context, input_token_account).await, 0);
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::error::ErrorCode;
+use raydium_cp_swap::states::AmmConfig;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_transfer_hook::{Counter, COUNTER_SEED, EXTRA_ACCOUNT_METAS_SEED};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000_000;
+
+#[tokio::test]
+async fn swap_route_two_hops_with_transfer_hook() {
+    let mut program_test = program_test();
+    let token_a_mint = add_mint(&mut program_test, 6);
+    let token_b_mint = add_mint(&mut program_test, 9);
+    let token_c_mint = add_transfer_hook_mint(&mut program_test, 6, test_transfer_hook::id());
+    let amm_config = AmmConfig {
+        token_0_lp_rate: 2500,
+        token_1_lp_rate: 2500,
+        ..Default::default()
+    };
+    let pool_ab = add_pool(
+        &mut program_test,
+        amm_config.clone(),
+        token_a_mint,
+        token_b_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    // token_c is token_0 of the second pool, so that hop swaps one for zero
+    let pool_cb = add_pool(
+        &mut program_test,
+        amm_config,
+        token_c_mint,
+        token_b_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let user = Keypair::new();
+    let token_a_account = Pubkey::new_unique();
+    let token_b_account = Pubkey::new_unique();
+    let token_c_account = Pubkey::new_unique();
+    for (address, mint, amount) in [
+        (token_a_account, &token_a_mint, AMOUNT_IN),
+        (token_b_account, &token_b_mint, 0),
+        (token_c_account, &token_c_mint, 0),
+    ] {
+        add_token_account(&mut program_test, address, mint, user.pubkey(), amount);
+    }
+    let mut context = program_test.start_with_context().await;
+
+    let (extra_account_meta_list, _) = Pubkey::find_program_address(
+        &[
+            EXTRA_ACCOUNT_METAS_SEED.as_bytes(),
+            token_c_mint.address.as_ref(),
+        ],
+        &test_transfer_hook::id(),
+    );
+    let (counter, _) = Pubkey::find_program_address(
+        &[COUNTER_SEED.as_bytes(), token_c_mint.address.as_ref()],
+        &test_transfer_hook::id(),
+    );
+    let instruction = Instruction {
+        program_id: test_transfer_hook::id(),
+        accounts: test_transfer_hook::accounts::InitializeExtraAccountMetaList {
+            payer: context.payer.pubkey(),
+            extra_account_meta_list,
+            mint: token_c_mint.address,
+            counter,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: test_transfer_hook::instruction::InitializeExtraAccountMetaList {}.data(),
+    };
+    process_instruction(&mut context, instruction, &[])
+        .await
+        .unwrap();
+
+    let first_hop = swap_accounts(
+        &pool_ab,
+        user.pubkey(),
+        token_a_account,
+        token_b_account,
+        true,
+    )
+    .to_account_metas(None);
+    // the second hop pays out token_c, so it carries the extra accounts of its hook
+    let mut second_hop = swap_accounts(
+        &pool_cb,
+        user.pubkey(),
+        token_b_account,
+        token_c_account,
+        false,
+    )
+    .to_account_metas(None);
+    second_hop.extend([
+        AccountMeta::new_readonly(extra_account_meta_list, false),
+        AccountMeta::new(counter, false),
+        AccountMeta::new_readonly(test_transfer_hook::id(), false),
+    ]);
+    let hop_accounts_lens = vec![first_hop.len() as u8, second_hop.len() as u8];
+    let mut accounts = raydium_cp_swap::accounts::SwapRoute {
+        payer: user.pubkey(),
+    }
+    .to_account_metas(None);
+    accounts.extend(first_hop);
+    accounts.extend(second_hop);
+    let route_instruction = |hop_accounts_lens: Vec<u8>| Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: accounts.clone(),
+        data: raydium_cp_swap::instruction::SwapRoute {
+            amount_in: AMOUNT_IN,
+            minimum_amount_out: 1,
+            hop_accounts_lens,
+        }
+        .data(),
+    };
+
+    // the lengths must cover the remaining accounts exactly
+    let mut short_lens = hop_accounts_lens.clone();
+    short_lens[1] -= 1;
+    let err = process_instruction(&mut context, route_instruction(short_lens), &[&user])
+        .await
+        .unwrap_err();
+    assert!(is_program_error(err, ErrorCode::InvalidInput.into()));
+
+    process_instruction(&mut context, route_instruction(hop_accounts_lens), &[&user])
+        .await
+        .unwrap();
+
+    let amount_out = token_amount(&mut context, token_c_account).await;
+    assert!(amount_out > 0);
+    assert_eq!(token_amount(&mut context, token_a_account).await, 0);
+    // the whole output of the first hop is the input of the second one
+    assert_eq!(token_amount(&mut context, token_b_account).await, 0);
+    assert_eq!(
+        token_amount(&mut context, pool_cb.token_1_vault).await - VAULT_AMOUNT,
+        VAULT_AMOUNT - token_amount(&mut context, pool_ab.token_1_vault).await
+    );
+    let counter = context
+        .banks_client
+        .get_account(counter)
+        .await
+        .unwrap()
+        .unwrap();
+    let counter = Counter::try_deserialize(&mut counter.data.as_slice()).unwrap();
+    assert_eq!(counter.transfers, 1);
+    assert_eq!(counter.amount, amount_out);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::states::AmmConfig;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_transfer_hook::{Counter, COUNTER_SEED, EXTRA_ACCOUNT_METAS_SEED};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000;
+const AMOUNT_IN: u64 = 1_000_000;
+
+#[tokio::test]
+async fn swap_invokes_transfer_hook() {
+    let mut program_test = program_test();
+    let token_0_mint = add_transfer_hook_mint(&mut program_test, 6, test_transfer_hook::id());
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let keys = add_pool(
+        &mut program_test,
+        AmmConfig {
+            token_0_lp_rate: 2500,
+            token_1_lp_rate: 2500,
+            ..Default::default()
+        },
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let user = Keypair::new();
+    let input_token_account = Pubkey::new_unique();
+    let output_token_account = Pubkey::new_unique();
+    add_token_account(
+        &mut program_test,
+        input_token_account,
+        &token_0_mint,
+        user.pubkey(),
+        AMOUNT_IN,
+    );
+    add_token_account(
+        &mut program_test,
+        output_token_account,
+        &token_1_mint,
+        user.pubkey(),
+        0,
+    );
+    let mut context = program_test.start_with_context().await;
+
+    // the extra accounts Token-2022 resolves for the hook of token_0
+    let (extra_account_meta_list, _) = Pubkey::find_program_address(
+        &[
+            EXTRA_ACCOUNT_METAS_SEED.as_bytes(),
+            token_0_mint.address.as_ref(),
+        ],
+        &test_transfer_hook::id(),
+    );
+    let (counter, _) = Pubkey::find_program_address(
+        &[COUNTER_SEED.as_bytes(), token_0_mint.address.as_ref()],
+        &test_transfer_hook::id(),
+    );
+    let instruction = Instruction {
+        program_id: test_transfer_hook::id(),
+        accounts: test_transfer_hook::accounts::InitializeExtraAccountMetaList {
+            payer: context.payer.pubkey(),
+            extra_account_meta_list,
+            mint: token_0_mint.address,
+            counter,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: test_transfer_hook::instruction::InitializeExtraAccountMetaList {}.data(),
+    };
+    process_instruction(&mut context, instruction, &[])
+        .await
+        .unwrap();
+
+    let mut accounts = raydium_cp_swap::accounts::Swap {
+        payer: user.pubkey(),
+        authority: keys.authority,
+        amm_config: keys.amm_config,
+        pool_state: keys.pool_id,
+        input_token_account: Some(input_token_account),
+        output_token_account: Some(output_token_account),
+        input_vault: keys.token_0_vault,
+        output_vault: keys.token_1_vault,
+        input_token_program: token_0_mint.token_program,
+        output_token_program: token_1_mint.token_program,
+        input_token_mint: token_0_mint.address,
+        output_token_mint: token_1_mint.address,
+        observation_state: keys.observation_state,
+        input_mint_registry_entry: Some(mint_registry_entry(&token_0_mint.address)),
+        output_mint_registry_entry: Some(mint_registry_entry(&token_1_mint.address)),
+        early_access: None,
+        launch_buy_state: None,
+        system_program: None,
+        referrer_token_account: None,
+        temp_wsol_account: None,
+    }
+    .to_account_metas(None);
+    accounts.extend([
+        AccountMeta::new_readonly(extra_account_meta_list, false),
+        AccountMeta::new(counter, false),
+        AccountMeta::new_readonly(test_transfer_hook::id(), false),
+    ]);
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts,
+        data: raydium_cp_swap::instruction::SwapBaseInput {
+            amount_in: AMOUNT_IN,
+            minimum_amount_out: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&user])
+        .await
+        .unwrap();
+
+    let counter = context
+        .banks_client
+        .get_account(counter)
+        .await
+        .unwrap()
+        .unwrap();
+    let counter = Counter::try_deserialize(&mut counter.data.as_slice()).unwrap();
+    assert_eq!(counter.transfers, 1);
+    assert_eq!(counter.amount, AMOUNT_IN);
+    assert_eq!(
+        token_amount(&mut context, keys.token_0_vault).await,
+        VAULT_AMOUNT + AMOUNT_IN
+    );
+    assert_eq!(token_amount(&mut context, input_token_account).await, 0);
+    assert!(token_amount(&mut context, output_token_account).await > 0);
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use raydium_cp_swap::states::AmmConfig;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const VAULT_AMOUNT: u64 = 1_000_000_000_000;
+const LP_AMOUNT: u64 = 100_000_000_000;
+
+#[tokio::test]
+async fn withdraw_single_sided_matches_withdraw_then_swap() {
+    let mut program_test = program_test();
+    let token_0_mint = add_mint(&mut program_test, 6);
+    let token_1_mint = add_mint(&mut program_test, 9);
+    let amm_config = AmmConfig {
+        token_0_lp_rate: 2500,
+        token_1_lp_rate: 2500,
+        ..Default::default()
+    };
+    // two identical pools, one withdrawn single sided and one withdrawn then swapped
+    let single_sided = add_pool(
+        &mut program_test,
+        amm_config.clone(),
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let withdraw_then_swap = add_pool(
+        &mut program_test,
+        amm_config,
+        token_0_mint,
+        token_1_mint,
+        VAULT_AMOUNT,
+        VAULT_AMOUNT,
+    );
+    let owner = Keypair::new();
+    let single_sided_lp_token = Pubkey::new_unique();
+    let withdraw_then_swap_lp_token = Pubkey::new_unique();
+    let single_sided_token_1 = Pubkey::new_unique();
+    let token_0_account = Pubkey::new_unique();
+    let token_1_account = Pubkey::new_unique();
+    for (address, mint, amount) in [
+        (single_sided_lp_token, &single_sided.lp_mint, LP_AMOUNT),
+        (
+            withdraw_then_swap_lp_token,
+            &withdraw_then_swap.lp_mint,
+            LP_AMOUNT,
+        ),
+        (single_sided_token_1, &token_1_mint, 0),
+        (token_0_account, &token_0_mint, 0),
+        (token_1_account, &token_1_mint, 0),
+    ] {
+        add_token_account(&mut program_test, address, mint, owner.pubkey(), amount);
+    }
+    let mut context = program_test.start_with_context().await;
+
+    // swap the token_0 share into token_1 within the pool
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::WithdrawSingleSided {
+            owner: owner.pubkey(),
+            authority: single_sided.authority,
+            amm_config: single_sided.amm_config,
+            pool_state: single_sided.pool_id,
+            owner_lp_token: single_sided_lp_token,
+            output_token_account: single_sided_token_1,
+            input_vault: single_sided.token_0_vault,
+            output_vault: single_sided.token_1_vault,
+            token_program: spl_token::id(),
+            output_token_program: spl_token::id(),
+            output_token_mint: token_1_mint.address,
+            lp_mint: single_sided.lp_mint.address,
+            observation_state: single_sided.observation_state,
+            input_token_mint: token_0_mint.address,
+            input_mint_registry_entry: mint_registry_entry(&token_0_mint.address),
+            output_mint_registry_entry: mint_registry_entry(&token_1_mint.address),
+            launch_buy_state: None,
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::WithdrawSingleSided {
+            lp_token_amount: LP_AMOUNT,
+            minimum_amount_out: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::Withdraw {
+            owner: owner.pubkey(),
+            authority: withdraw_then_swap.authority,
+            pool_state: withdraw_then_swap.pool_id,
+            owner_lp_token: withdraw_then_swap_lp_token,
+            token_0_account: Some(token_0_account),
+            token_1_account: Some(token_1_account),
+            token_0_vault: withdraw_then_swap.token_0_vault,
+            token_1_vault: withdraw_then_swap.token_1_vault,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+            vault_0_mint: token_0_mint.address,
+            vault_1_mint: token_1_mint.address,
+            lp_mint: withdraw_then_swap.lp_mint.address,
+            memo_program: spl_memo::id(),
+            amm_config: withdraw_then_swap.amm_config,
+            system_program: None,
+            temp_wsol_account: None,
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::Withdraw {
+            lp_token_amount: LP_AMOUNT,
+            minimum_token_0_amount: 1,
+            minimum_token_1_amount: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+    let instruction = Instruction {
+        program_id: raydium_cp_swap::id(),
+        accounts: raydium_cp_swap::accounts::Swap {
+            payer: owner.pubkey(),
+            authority: withdraw_then_swap.authority,
+            amm_config: withdraw_then_swap.amm_config,
+            pool_state: withdraw_then_swap.pool_id,
+            input_token_account: Some(token_0_account),
+            output_token_account: Some(token_1_account),
+            input_vault: withdraw_then_swap.token_0_vault,
+            output_vault: withdraw_then_swap.token_1_vault,
+            input_token_program: spl_token::id(),
+            output_token_program: spl_token::id(),
+            input_token_mint: token_0_mint.address,
+            output_token_mint: token_1_mint.address,
+            observation_state: withdraw_then_swap.observation_state,
+            input_mint_registry_entry: Some(mint_registry_entry(&token_0_mint.address)),
+            output_mint_registry_entry: Some(mint_registry_entry(&token_1_mint.address)),
+            early_access: None,
+            launch_buy_state: None,
+            system_program: None,
+            referrer_token_account: None,
+            temp_wsol_account: None,
+        }
+        .to_account_metas(None),
+        data: raydium_cp_swap::instruction::SwapBaseInput {
+            amount_in: token_amount(&mut context, token_0_account).await,
+            minimum_amount_out: 1,
+        }
+        .data(),
+    };
+    process_instruction(&mut context, instruction, &[&owner])
+        .await
+        .unwrap();
+
+    let amount_out = token_amount(&mut context, single_sided_token_1).await;
+    assert!(amount_out > 0);
+    assert_eq!(
+        amount_out,
+        token_amount(&mut context, token_1_account).await
+    );
+    // the lp share is priced on the curve, far below the pool share of token_1
+    assert!(amount_out < LP_AMOUNT);
+    let single_sided_pool = pool_state(&mut context, single_sided.pool_id).await;
+    let withdraw_then_swap_pool = pool_state(&mut context, withdraw_then_swap.pool_id).await;
+    assert_eq!({ single_sided_pool.lp_supply }, {
+        withdraw_then_swap_pool.lp_supply
+    });
+    assert_eq!({ single_sided_pool.amm.real_sol_reserves }, {
+        withdraw_then_swap_pool.amm.real_sol_reserves
+    });
+}
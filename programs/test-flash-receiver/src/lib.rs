@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+declare_id!("CKDbmvuGpWJ5fdWWHodYPsqBG4hYcTg2rnkuUXTUMzKM");
+
+/// Minimal flash swap receiver for local testing, repays the given amounts to the vaults.
+/// Repaying less than the borrowed amounts plus the flash fee makes `flash_swap` fail.
+#[program]
+pub mod test_flash_receiver {
+    use super::*;
+
+    /// Invoked by `flash_swap` with the borrowed tokens in `token_0_account` and `token_1_account`
+    pub fn on_flash_swap(
+        ctx: Context<OnFlashSwap>,
+        repay_amount_0: u64,
+        repay_amount_1: u64,
+    ) -> Result<()> {
+        if repay_amount_0 > 0 {
+            transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program_0.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.token_0_account.to_account_info(),
+                        mint: ctx.accounts.vault_0_mint.to_account_info(),
+                        to: ctx.accounts.token_0_vault.to_account_info(),
+                        authority: ctx.accounts.payer.to_account_info(),
+                    },
+                ),
+                repay_amount_0,
+                ctx.accounts.vault_0_mint.decimals,
+            )?;
+        }
+        if repay_amount_1 > 0 {
+            transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.token_program_1.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.token_1_account.to_account_info(),
+                        mint: ctx.accounts.vault_1_mint.to_account_info(),
+                        to: ctx.accounts.token_1_vault.to_account_info(),
+                        authority: ctx.accounts.payer.to_account_info(),
+                    },
+                ),
+                repay_amount_1,
+                ctx.accounts.vault_1_mint.decimals,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The accounts passed by `flash_swap` in order, followed by its `remaining_accounts`
+#[derive(Accounts)]
+pub struct OnFlashSwap<'info> {
+    /// The borrower, signer of the outer flash swap
+    pub payer: Signer<'info>,
+
+    #[account(mut, token::authority = payer)]
+    pub token_0_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::authority = payer)]
+    pub token_1_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub token_0_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub token_1_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = token_0_vault.mint)]
+    pub vault_0_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = token_1_vault.mint)]
+    pub vault_1_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program_0: Interface<'info, TokenInterface>,
+
+    pub token_program_1: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+
+declare_id!("EcHAchy6tYVnjC81wv8jjGkPnuYV93HpBkudHgw9GnHv");
+
+pub const EXTRA_ACCOUNT_METAS_SEED: &str = "extra-account-metas";
+pub const COUNTER_SEED: &str = "counter";
+
+/// Minimal transfer hook for local testing, counts the transfers of a mint.
+/// The counter PDA is the single extra account, resolved by Token-2022 from the
+/// `ExtraAccountMetaList` of the mint.
+#[program]
+pub mod test_transfer_hook {
+    use super::*;
+
+    /// Create the extra account meta list and the counter of a mint
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        let account_metas = vec![ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: COUNTER_SEED.as_bytes().to_vec(),
+                },
+                // the mint of the transfer
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            true,
+        )?];
+        let account_size = ExtraAccountMetaList::size_of(account_metas.len())?;
+        let lamports = Rent::get()?.minimum_balance(account_size);
+
+        let mint = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            EXTRA_ACCOUNT_METAS_SEED.as_bytes(),
+            mint.as_ref(),
+            &[ctx.bumps.extra_account_meta_list],
+        ]];
+        create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.extra_account_meta_list.to_account_info(),
+                },
+            )
+            .with_signer(signer_seeds),
+            lamports,
+            account_size as u64,
+            ctx.program_id,
+        )?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &account_metas,
+        )?;
+        Ok(())
+    }
+
+    /// Invoked by Token-2022 on every `transfer_checked` of the mint
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.transfers = counter.transfers.checked_add(1).unwrap();
+        counter.amount = counter.amount.checked_add(amount).unwrap();
+        Ok(())
+    }
+
+    /// Token-2022 calls the hook with the spl interface discriminator instead of anchor's
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            TransferHookInstruction::Execute { amount } => {
+                __private::__global::transfer_hook(program_id, accounts, &amount.to_le_bytes())
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: created with the tlv layout of the transfer hook interface
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: the Token-2022 mint with this program as transfer hook
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [COUNTER_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = Counter::LEN
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts in the order of the transfer hook `Execute` instruction
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    /// CHECK: source token account
+    pub source_token: UncheckedAccount<'info>,
+    /// CHECK: mint of the transfer
+    pub mint: UncheckedAccount<'info>,
+    /// CHECK: destination token account
+    pub destination_token: UncheckedAccount<'info>,
+    /// CHECK: owner or delegate of the source token account
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: the extra account meta list of the mint
+    #[account(
+        seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [COUNTER_SEED.as_bytes(), mint.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+}
+
+/// Number and total amount of the transfers of a mint
+#[account]
+#[derive(Default, Debug)]
+pub struct Counter {
+    pub transfers: u64,
+    pub amount: u64,
+}
+
+impl Counter {
+    pub const LEN: usize = 8 + 8 * 2;
+}